@@ -0,0 +1,8 @@
+/// The declarations of the HDF5 C API used by `src/value.rs` are named after the version of the
+/// library found by `hdf5-sys`
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(hdf5_1_12)");
+    if std::env::var_os("DEP_HDF5_VERSION_1_12_0").is_some() {
+        println!("cargo::rustc-cfg=hdf5_1_12");
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::hdf;
+use crate::inspect;
+use crate::validate;
+
+pub const USAGE: &str = "\
+Usage:
+    nwbview [FILE[#/PATH]...]                       Launch the graphical viewer with the given
+                                                    files, directories or glob patterns, and
+                                                    reveal the object at PATH
+    nwbview tree [--depth N] [--filter PATTERN] FILE...
+                                                    Print the hierarchy of the given files
+    nwbview validate FILE...                        Check the files against their cached
+                                                    specification, exits with 1 on violations
+    nwbview inspect [--json] FILE...                Report the best practices not followed by
+                                                    the files, exits with 1 on critical findings
+    nwbview help                                    Show this message
+
+Options for `tree`:
+    -d, --depth N           Only print objects up to N levels below the root
+    -f, --filter PATTERN    Only print objects whose path contains PATTERN
+
+Options for `inspect`:
+    --json                  Print the findings as a JSON array";
+
+/// The action requested on the command line
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Files to open in the viewer
+    Gui(Vec<String>),
+    Tree(TreeOptions),
+    Validate(Vec<String>),
+    Inspect(InspectOptions),
+    Help,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TreeOptions {
+    pub files: Vec<String>,
+    pub max_depth: Option<usize>,
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct InspectOptions {
+    pub files: Vec<String>,
+    pub json: bool,
+}
+
+/// Parse the command line arguments, without the program name
+pub(crate) fn parse_args(args: &[String]) -> Result<Command, String> {
+    let Some(command) = args.first() else {
+        return Ok(Command::Gui(vec![]));
+    };
+    match command.as_str() {
+        "tree" => parse_tree_args(&args[1..]).map(Command::Tree),
+        "validate" => match args[1..].iter().find(|x| x.starts_with('-')) {
+            Some(flag) => Err(format!("Unknown option '{flag}'")),
+            None if args.len() == 1 => Err("No file given to `validate`".to_string()),
+            None => Ok(Command::Validate(args[1..].to_vec())),
+        },
+        "inspect" => parse_inspect_args(&args[1..]).map(Command::Inspect),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => match args.iter().find(|x| x.starts_with('-')) {
+            Some(flag) => Err(format!("Unknown option '{flag}'")),
+            None => Ok(Command::Gui(args.to_vec())),
+        },
+    }
+}
+
+/// File to open in the viewer, with the object to reveal, e.g. `session.nwb#/acquisition/lick`
+#[derive(Debug, PartialEq)]
+pub struct OpenTarget {
+    pub file: String,
+    pub object: Option<String>,
+}
+
+pub(crate) fn parse_open_target(arg: &str) -> OpenTarget {
+    match arg.rsplit_once("#/") {
+        Some((file, object)) => OpenTarget {
+            file: file.to_string(),
+            object: Some(format!("/{object}")),
+        },
+        None => OpenTarget {
+            file: arg.to_string(),
+            object: None,
+        },
+    }
+}
+
+/// Files designated by an argument: the NWB files below a directory, the files matching a
+/// pattern with `*` and `?` wildcards, or else the argument itself
+pub(crate) fn expand_path(path: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    if Path::new(path).is_dir() {
+        nwb_files(Path::new(path), &mut files);
+    } else if path.contains(['*', '?']) {
+        files = glob(Path::new(path));
+    } else {
+        return vec![path.to_string()];
+    }
+    files.sort();
+    files.iter().map(|x| x.display().to_string()).collect()
+}
+
+fn nwb_files(directory: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for path in entries.flatten().map(|x| x.path()) {
+        if path.is_dir() {
+            nwb_files(&path, files);
+        } else if path.extension().is_some_and(|x| x == "nwb") {
+            files.push(path);
+        }
+    }
+}
+
+/// Paths matching a pattern, each component of the path may hold wildcards
+fn glob(pattern: &Path) -> Vec<PathBuf> {
+    let mut matches = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let name = component.as_os_str().to_string_lossy();
+        if !name.contains(['*', '?']) {
+            matches.iter_mut().for_each(|x| x.push(component));
+            continue;
+        }
+        matches = matches
+            .iter()
+            .flat_map(|parent| {
+                let directory = match parent.as_os_str().is_empty() {
+                    true => Path::new("."),
+                    false => parent.as_path(),
+                };
+                std::fs::read_dir(directory)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter(|x| wildcard_match(&name, &x.file_name().to_string_lossy()))
+                    .map(|x| parent.join(x.file_name()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+    matches.retain(|x| x.exists());
+    matches
+}
+
+/// Whether the name matches the pattern, `*` matches any sequence of characters and `?` any
+/// single character
+pub(crate) fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Position after the last star in the pattern, and the position in the name it matched up to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, n));
+                p += 1;
+            }
+            Some(x) if *x == '?' || *x == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((after, matched)) => {
+                    p = after;
+                    n = matched + 1;
+                    star = Some((after, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|x| *x == '*')
+}
+
+fn parse_tree_args(args: &[String]) -> Result<TreeOptions, String> {
+    let mut options = TreeOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--depth" => {
+                let value = args.next().ok_or("Missing value for --depth")?;
+                let depth = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid depth '{value}'"))?;
+                options.max_depth = Some(depth);
+            }
+            "-f" | "--filter" => {
+                let value = args.next().ok_or("Missing value for --filter")?;
+                options.filter = Some(value.to_string());
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option '{flag}'")),
+            path => options.files.push(path.to_string()),
+        }
+    }
+    if options.files.is_empty() {
+        return Err("No file given to `tree`".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_inspect_args(args: &[String]) -> Result<InspectOptions, String> {
+    let mut options = InspectOptions::default();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => options.json = true,
+            flag if flag.starts_with('-') => return Err(format!("Unknown option '{flag}'")),
+            path => options.files.push(path.to_string()),
+        }
+    }
+    if options.files.is_empty() {
+        return Err("No file given to `inspect`".to_string());
+    }
+    Ok(options)
+}
+
+/// Print the hierarchy of every requested file, returns false if a file could not be read
+pub(crate) fn run_tree(options: &TreeOptions) -> bool {
+    let mut success = true;
+    for path in &options.files {
+        match hdf::read_nwb_file(path) {
+            None => {
+                eprintln!("Could not load {path}");
+                success = false;
+            }
+            Some(file_tree) => {
+                println!("{path}");
+                print!(
+                    "{}",
+                    format_tree(
+                        &file_tree.tree,
+                        options.max_depth,
+                        options.filter.as_deref()
+                    )
+                );
+            }
+        }
+    }
+    success
+}
+
+/// Print the violations found in every requested file, returns false if any was found or if a
+/// file could not be read
+pub(crate) fn run_validate(files: &[String]) -> bool {
+    let mut success = true;
+    for path in files {
+        let Some(file_tree) = hdf::read_nwb_file(path) else {
+            eprintln!("Could not load {path}");
+            success = false;
+            continue;
+        };
+        let violations = validate::validate(&file_tree.tree, &file_tree.specification);
+        for violation in &violations {
+            println!("{path}:{}: {}", violation.path, violation.message);
+        }
+        println!("{path}: {} violation(s)", violations.len());
+        success &= violations.is_empty();
+    }
+    success
+}
+
+/// Print the best-practice findings of every requested file, returns false if a critical one was
+/// found or if a file could not be read
+pub(crate) fn run_inspect(options: &InspectOptions) -> bool {
+    let checks = inspect::default_checks();
+    let mut success = true;
+    let mut json: Vec<serde_json::Value> = Vec::new();
+    for path in &options.files {
+        let Some(file_tree) = hdf::read_nwb_file(path) else {
+            eprintln!("Could not load {path}");
+            success = false;
+            continue;
+        };
+        let findings = inspect::inspect(&file_tree.tree, &checks);
+        success &= !findings
+            .iter()
+            .any(|x| x.severity == inspect::Severity::Critical);
+        if options.json {
+            if let serde_json::Value::Array(values) = inspect::to_json(path, &findings) {
+                json.extend(values);
+            }
+            continue;
+        }
+        for finding in &findings {
+            println!(
+                "{path}:{}: {}: {} [{}]",
+                finding.path, finding.severity, finding.message, finding.check
+            );
+        }
+        println!("{path}: {} finding(s)", findings.len());
+    }
+    if options.json {
+        println!("{}", serde_json::Value::Array(json));
+    }
+    success
+}
+
+/// Render a group tree as indented text, one object per line
+pub(crate) fn format_tree(
+    tree: &hdf::GroupTree,
+    max_depth: Option<usize>,
+    filter: Option<&str>,
+) -> String {
+    let mut output = String::new();
+    format_group(tree, 1, max_depth, filter, &mut output);
+    output
+}
+
+fn format_group(
+    group: &hdf::GroupTree,
+    depth: usize,
+    max_depth: Option<usize>,
+    filter: Option<&str>,
+    output: &mut String,
+) {
+    if max_depth.is_some_and(|max| depth > max) {
+        return;
+    }
+    let indent = "  ".repeat(depth - 1);
+    for subgroup in &group.groups {
+        if !has_match(subgroup, depth, max_depth, filter) {
+            continue;
+        }
+        let name = subgroup.handler.name();
+        writeln!(
+            output,
+            "{indent}{}/{}",
+            hdf::last_component(&name),
+            type_suffix(&subgroup.neurodata_type)
+        )
+        .ok();
+        format_group(subgroup, depth + 1, max_depth, filter, output);
+    }
+    for dataset in &group.datasets {
+        if filter.is_some_and(|pattern| !dataset.name.contains(pattern)) {
+            continue;
+        }
+        let name = hdf::last_component(&dataset.name);
+        let description = match group.handler.dataset(name) {
+            Err(_) => "(unreadable)".to_string(),
+            Ok(ds) => describe_dataset(&ds),
+        };
+        writeln!(
+            output,
+            "{indent}{name}  {description}{}",
+            type_suffix(&dataset.neurodata_type)
+        )
+        .ok();
+    }
+    for link in &group.links {
+        if filter.is_some_and(|pattern| !link.name.contains(pattern)) {
+            continue;
+        }
+        writeln!(
+            output,
+            "{indent}{}  -> {}",
+            hdf::last_component(&link.name),
+            describe_link(&group.handler, link)
+        )
+        .ok();
+    }
+}
+
+/// Neurodata type appended to the objects that have one, e.g. `  [TimeSeries]`
+fn type_suffix(neurodata_type: &Option<hdf::NeurodataType>) -> String {
+    match neurodata_type {
+        None => String::new(),
+        Some(x) => format!("  [{}]", x.name),
+    }
+}
+
+/// Target of a link of `group`, e.g. `/acquisition/x` or `other.nwb:/acquisition/x`
+fn describe_link(group: &hdf5::Group, link: &hdf::LinkTree) -> String {
+    if link.kind == hdf5::LinkType::External {
+        return match hdf::external_target(group, link) {
+            None => "(dangling)".to_string(),
+            Some((target, file)) => format!("{file}:{target}"),
+        };
+    }
+    match &link.target {
+        None => "(dangling)".to_string(),
+        Some(target) => target.clone(),
+    }
+}
+
+/// Whether the group or one of its printed descendants matches the filter
+fn has_match(
+    group: &hdf::GroupTree,
+    depth: usize,
+    max_depth: Option<usize>,
+    filter: Option<&str>,
+) -> bool {
+    let Some(pattern) = filter else {
+        return true;
+    };
+    if group.handler.name().contains(pattern) {
+        return true;
+    }
+    if max_depth.is_some_and(|max| depth >= max) {
+        return false;
+    }
+    group.datasets.iter().any(|x| x.name.contains(pattern))
+        || group.links.iter().any(|x| x.name.contains(pattern))
+        || group
+            .groups
+            .iter()
+            .any(|x| has_match(x, depth + 1, max_depth, filter))
+}
+
+/// Shape and type of a dataset, e.g. `(1000, 32) float64`
+fn describe_dataset(ds: &hdf5::Dataset) -> String {
+    let shape = if ds.is_scalar() {
+        "scalar".to_string()
+    } else {
+        let dims: Vec<String> = ds.shape().iter().map(|x| x.to_string()).collect();
+        match dims.len() {
+            1 => format!("({},)", dims[0]),
+            _ => format!("({})", dims.join(", ")),
+        }
+    };
+    let dtype = match ds.dtype().and_then(|x| x.to_descriptor()) {
+        Err(_) => "unknown".to_string(),
+        Ok(descriptor) => descriptor.to_string(),
+    };
+    format!("{shape} {dtype}")
+}
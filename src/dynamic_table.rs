@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+use crate::hdf;
+use crate::spec;
+use crate::value;
+use hdf5::types::{TypeDescriptor, VarLenAscii, VarLenUnicode};
+
+/// Column of a DynamicTable, one element of its dataset per row
+pub struct Column {
+    pub name: String,
+    pub description: Option<String>,
+    pub data: hdf5::Dataset,
+    /// VectorIndex datasets of a ragged column, the one splitting the rows first. Each index
+    /// holds the end offsets of its elements in the next one, or in `data` for the last one.
+    pub indices: Vec<hdf5::Dataset>,
+    /// Whether the cells hold paths of referenced objects
+    pub links: bool,
+    /// Table whose rows are selected by the cells of a DynamicTableRegion column
+    pub region: Option<Region>,
+}
+
+/// Rows of a table selected by a DynamicTableRegion, e.g. the electrodes of a recording
+pub struct Region {
+    /// Path of the referenced table
+    pub table: String,
+    /// Label of every row of the table, its id followed by its key field, e.g. `12 (CA1)`
+    pub labels: Vec<String>,
+}
+
+/// Table of an NWB file, e.g. `trials` or `units`, whose columns are parallel datasets
+pub struct DynamicTable {
+    pub name: String,
+    pub description: Option<String>,
+    /// Identifiers of the rows
+    pub id: hdf5::Dataset,
+    /// Columns in the order of the `colnames` attribute
+    pub columns: Vec<Column>,
+}
+
+/// Columns identifying the rows of a table besides their id, by order of preference
+const KEY_COLUMNS: [&str; 4] = ["label", "name", "location", "group_name"];
+
+/// Whether the group is a DynamicTable or extends it. Files without cached specification are
+/// recognized by the `colnames` attribute of the tables.
+pub(crate) fn is_dynamic_table(group: &hdf::GroupTree, registry: &spec::Registry) -> bool {
+    let typed = group
+        .neurodata_type
+        .as_ref()
+        .is_some_and(|x| registry.is_a(x, "DynamicTable"));
+    typed || group.attributes.iter().any(|x| x.name == "colnames")
+}
+
+impl DynamicTable {
+    pub(crate) fn open(group: &hdf5::Group) -> hdf5::Result<DynamicTable> {
+        let attributes = hdf::read_attributes(group);
+        let names = match attributes.iter().find(|x| x.name == "colnames") {
+            Some(colnames) => text_values(&colnames.value),
+            None => group
+                .member_names()?
+                .into_iter()
+                .filter(|x| x != "id" && !x.ends_with("_index") && group.dataset(x).is_ok())
+                .collect(),
+        };
+        let columns = names
+            .into_iter()
+            .filter_map(|name| Column::open(group, name))
+            .collect();
+        Ok(DynamicTable {
+            name: group.name(),
+            description: hdf::text_attribute(&attributes, "description").map(str::to_string),
+            id: group.dataset("id")?,
+            columns,
+        })
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.id.shape().first().copied().unwrap_or(0)
+    }
+
+    pub(crate) fn read_ids(&self, rows: Range<usize>) -> hdf5::Result<Vec<i64>> {
+        self.id.read_slice_1d::<i64, _>(rows).map(|x| x.to_vec())
+    }
+}
+
+impl Column {
+    /// Column stored in the dataset `name` of a table, with the indices splitting it into rows
+    pub(crate) fn open(group: &hdf5::Group, name: String) -> Option<Column> {
+        let data = group.dataset(&name).ok()?;
+        let description =
+            hdf::text_attribute(&hdf::read_attributes(&data), "description").map(str::to_string);
+        let mut indices = Vec::new();
+        let mut index_name = format!("{name}_index");
+        while let Ok(index) = group.dataset(&index_name) {
+            indices.insert(0, index);
+            index_name.push_str("_index");
+        }
+        let links = indices.is_empty()
+            && matches!(
+                data.dtype().and_then(|x| x.to_descriptor()),
+                Ok(TypeDescriptor::Reference(hdf5::types::Reference::Object))
+            );
+        let region = Region::open(&data);
+        Some(Column {
+            name,
+            description,
+            data,
+            indices,
+            links,
+            region,
+        })
+    }
+}
+
+impl Region {
+    /// Region of a dataset of row indices, from the table referenced by its `table` attribute.
+    /// The labels of all the rows are read at once, such tables are usually small.
+    pub(crate) fn open(data: &hdf5::Dataset) -> Option<Region> {
+        let reference = data
+            .attr("table")
+            .ok()?
+            .read_scalar::<hdf5::ObjectReference1>()
+            .ok()?;
+        let Ok(hdf5::ReferencedObject::Group(table)) = data.dereference(&reference) else {
+            return None;
+        };
+        let id = table.dataset("id").ok()?;
+        let n_rows = id.size();
+        let ids: Vec<String> = read_cells(&id, 0..n_rows).ok()?;
+        let keys = KEY_COLUMNS
+            .iter()
+            .filter(|x| !table.link_exists(&format!("{x}_index")))
+            .find_map(|x| read_cells(&table.dataset(x).ok()?, 0..n_rows).ok());
+        let labels = match keys {
+            None => ids,
+            Some(keys) => ids
+                .into_iter()
+                .zip(keys)
+                .map(|(id, key)| format!("{id} ({key})"))
+                .collect(),
+        };
+        Some(Region {
+            table: table.name(),
+            labels,
+        })
+    }
+
+    pub fn label(&self, row: i64) -> String {
+        match usize::try_from(row).ok().and_then(|x| self.labels.get(x)) {
+            Some(label) => label.clone(),
+            None => format!("{row} (out of range)"),
+        }
+    }
+}
+
+/// Labels of the channels of an ElectricalSeries, from the rows of the electrodes table selected
+/// by its `electrodes` region, e.g. `12 (CA1)`
+pub(crate) fn electrode_labels(group: &hdf5::Group) -> Option<Vec<String>> {
+    let data = group.dataset("electrodes").ok()?;
+    let region = Region::open(&data)?;
+    let rows: Vec<i64> = data.read_raw().ok()?;
+    Some(rows.iter().map(|x| region.label(*x)).collect())
+}
+
+/// Text of the elements of an attribute, a single text gives a single element
+fn text_values(value: &hdf::AttributeValue) -> Vec<String> {
+    match value {
+        hdf::AttributeValue::Array(values) => values.iter().flat_map(text_values).collect(),
+        hdf::AttributeValue::Text(x) => vec![x.clone()],
+        _ => vec![],
+    }
+}
+
+/// Text of the cells of a column in the given rows. The cells of ragged columns list their
+/// elements before their count, e.g. `[0.1, 0.5, 0.9] (3)`.
+pub(crate) fn read_column(column: &Column, rows: Range<usize>) -> hdf5::Result<Vec<String>> {
+    let Some((index, inner)) = column.indices.split_first() else {
+        return read_cells(&column.data, rows);
+    };
+    let elements = read_elements(&column.data, inner, index_bounds(index, rows)?)?;
+    Ok(elements.iter().map(|x| value::format_array(x)).collect())
+}
+
+/// Ranges of the elements of each row in the indexed dataset, from the end offsets of the rows
+/// stored by a VectorIndex
+pub(crate) fn index_bounds(
+    index: &hdf5::Dataset,
+    rows: Range<usize>,
+) -> hdf5::Result<Vec<Range<usize>>> {
+    let ends = index
+        .read_slice_1d::<u64, _>(rows.start.saturating_sub(1)..rows.end)?
+        .to_vec();
+    let (mut start, ends) = match rows.start {
+        0 => (0, &ends[..]),
+        _ => (ends[0] as usize, &ends[1..]),
+    };
+    let mut bounds = Vec::with_capacity(ends.len());
+    for end in ends.iter().map(|x| *x as usize) {
+        if end < start {
+            return Err(format!("The offsets of {} are not ascending", index.name()).into());
+        }
+        bounds.push(start..end);
+        start = end;
+    }
+    Ok(bounds)
+}
+
+/// Offsets in the data of the cells of a column in the given rows, the elements of the cell `i`
+/// are between the offsets `i` and `i + 1`
+fn data_offsets(indices: &[hdf5::Dataset], rows: Range<usize>) -> hdf5::Result<Vec<usize>> {
+    let mut offsets: Vec<usize> = (rows.start..=rows.end).collect();
+    for index in indices {
+        let first = offsets[0];
+        let bounds = index_bounds(index, first..offsets[offsets.len() - 1])?;
+        offsets = offsets
+            .iter()
+            .map(|x| match x.checked_sub(first + 1) {
+                None => bounds.first().map_or(0, |x| x.start),
+                Some(i) => bounds[i].end,
+            })
+            .collect();
+    }
+    Ok(offsets)
+}
+
+/// Values of each cell of a one-dimensional column, the lists of doubly ragged columns are
+/// concatenated
+pub(crate) fn read_values<T: hdf5::H5Type + Clone>(
+    column: &Column,
+    rows: Range<usize>,
+) -> hdf5::Result<Vec<Vec<T>>> {
+    let offsets = data_offsets(&column.indices, rows)?;
+    let first = offsets[0];
+    let values = column
+        .data
+        .read_slice_1d::<T, _>(first..offsets[offsets.len() - 1])?
+        .to_vec();
+    Ok(offsets
+        .windows(2)
+        .map(|x| values[x[0] - first..x[1] - first].to_vec())
+        .collect())
+}
+
+/// Text of the elements in each range of a dataset, which is split again by the inner indices
+fn read_elements(
+    data: &hdf5::Dataset,
+    indices: &[hdf5::Dataset],
+    bounds: Vec<Range<usize>>,
+) -> hdf5::Result<Vec<Vec<String>>> {
+    let (Some(first), Some(last)) = (bounds.first(), bounds.last()) else {
+        return Ok(vec![]);
+    };
+    let span = first.start..last.end;
+    let texts = match indices.split_first() {
+        None => read_cells(data, span.clone())?,
+        Some((index, inner)) => read_elements(data, inner, index_bounds(index, span.clone())?)?
+            .iter()
+            .map(|x| value::format_array(x))
+            .collect(),
+    };
+    Ok(bounds
+        .into_iter()
+        .map(|x| texts[x.start - span.start..x.end - span.start].to_vec())
+        .collect())
+}
+
+/// Text of the elements of a dataset in the given rows, rows of 2D datasets are shown as arrays
+pub(crate) fn read_cells(data: &hdf5::Dataset, rows: Range<usize>) -> hdf5::Result<Vec<String>> {
+    let descriptor = data.dtype()?.to_descriptor()?;
+    match data.ndim() {
+        1 => {}
+        2 if is_numeric(&descriptor) => {
+            let values = data.read_slice_2d::<f64, _>((rows, ..))?;
+            return Ok(values
+                .rows()
+                .into_iter()
+                .map(|x| value::format_array(&x.to_vec()))
+                .collect());
+        }
+        n => return Err(format!("{n}-dimensional {descriptor} columns are not supported").into()),
+    }
+    macro_rules! read_as {
+        ($ty:ty) => {
+            data.read_slice_1d::<$ty, _>(rows)
+                .map(|x| x.iter().map(|x| x.to_string()).collect())
+        };
+    }
+    match &descriptor {
+        TypeDescriptor::Float(_) => read_as!(f64),
+        TypeDescriptor::Integer(_) => read_as!(i64),
+        TypeDescriptor::Unsigned(_) => read_as!(u64),
+        TypeDescriptor::Boolean => read_as!(bool),
+        TypeDescriptor::VarLenUnicode => read_as!(VarLenUnicode),
+        TypeDescriptor::VarLenAscii => read_as!(VarLenAscii),
+        TypeDescriptor::Enum(enum_type) => data.read_slice_1d::<i64, _>(rows).map(|x| {
+            x.iter()
+                .map(|x| match hdf::enum_member_name(enum_type, *x) {
+                    Some(name) => name.to_string(),
+                    None => x.to_string(),
+                })
+                .collect()
+        }),
+        TypeDescriptor::Reference(hdf5::types::Reference::Object) => data
+            .read_slice_1d::<hdf5::ObjectReference1, _>(rows)
+            .map(|x| x.iter().map(|x| hdf::reference_path(data, x)).collect()),
+        TypeDescriptor::Compound(_) => {
+            value::read(data, rows).map(|x| x.iter().map(|x| x.to_string()).collect())
+        }
+        TypeDescriptor::VarLenArray(base) if is_numeric(base) => data
+            .read_slice_1d::<hdf5::types::VarLenArray<f64>, _>(rows)
+            .map(|x| {
+                x.iter()
+                    .map(|x| value::format_array(x.as_slice()))
+                    .collect()
+            }),
+        _ => Err(format!("Columns of {descriptor} are not supported").into()),
+    }
+}
+
+fn is_numeric(descriptor: &TypeDescriptor) -> bool {
+    matches!(
+        descriptor,
+        TypeDescriptor::Float(_) | TypeDescriptor::Integer(_) | TypeDescriptor::Unsigned(_)
+    )
+}
@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+
+use crate::display_traits::{Show, View};
+use crate::dynamic_table::{self, DynamicTable};
+use crate::navigation;
+use crate::table;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+/// Number of rows read from the file at once
+const CHUNK_ROWS: usize = 256;
+/// Number of chunks kept in memory
+const CACHE_SIZE: usize = 16;
+
+/// Rows of a chunk, the columns are read independently so that one failing does not hide the
+/// others
+struct Chunk {
+    ids: Result<Vec<i64>, String>,
+    columns: Vec<Result<Vec<Cell>, String>>,
+}
+
+#[derive(Clone)]
+enum Cell {
+    Text(String),
+    /// Rows of the table referenced by a region column
+    Rows(Vec<i64>),
+}
+
+/// Shows the columns of a DynamicTable side by side, one row per id
+///
+/// The rows are read lazily from the datasets when they become visible.
+pub struct DynamicTableWindow {
+    file: String,
+    table: DynamicTable,
+    /// Recently read chunks of rows, the most recently used last
+    cache: VecDeque<(usize, Chunk)>,
+    /// Title and full text of the clicked truncated cell
+    expanded_cell: Option<(String, String)>,
+    /// Row to scroll to on the next frame
+    scroll_to: Option<usize>,
+    /// Row reached through a region, highlighted
+    selected_row: Option<usize>,
+}
+
+impl DynamicTableWindow {
+    pub fn new(group: &hdf5::Group) -> Result<Self, String> {
+        Ok(Self {
+            file: group.filename(),
+            table: DynamicTable::open(group).map_err(|e| e.to_string())?,
+            cache: VecDeque::new(),
+            expanded_cell: None,
+            scroll_to: None,
+            selected_row: None,
+        })
+    }
+
+    pub fn scroll_to_row(&mut self, row: usize) {
+        self.scroll_to = Some(row);
+        self.selected_row = Some(row);
+    }
+
+    fn read_chunk(&self, chunk: usize) -> Chunk {
+        let start = chunk * CHUNK_ROWS;
+        let rows = start..(start + CHUNK_ROWS).min(self.table.n_rows());
+        Chunk {
+            ids: self.table.read_ids(rows.clone()).map_err(|e| e.to_string()),
+            columns: self
+                .table
+                .columns
+                .iter()
+                .map(|x| {
+                    let cells = match x.region {
+                        Some(_) => dynamic_table::read_values(x, rows.clone())
+                            .map(|x| x.into_iter().map(Cell::Rows).collect()),
+                        None => dynamic_table::read_column(x, rows.clone())
+                            .map(|x| x.into_iter().map(Cell::Text).collect()),
+                    };
+                    cells.map_err(|e| e.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    /// Rows of the chunk holding the given row, reading it if it is not cached
+    fn chunk(&mut self, row: usize) -> &Chunk {
+        let chunk = row / CHUNK_ROWS;
+        match self.cache.iter().position(|x| x.0 == chunk) {
+            Some(position) => {
+                let entry = self.cache.remove(position).unwrap();
+                self.cache.push_back(entry);
+            }
+            None => {
+                if self.cache.len() >= CACHE_SIZE {
+                    self.cache.pop_front();
+                }
+                let values = self.read_chunk(chunk);
+                self.cache.push_back((chunk, values));
+            }
+        }
+        &self.cache.back().unwrap().1
+    }
+
+    fn table_ui(&mut self, ui: &mut egui::Ui) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let n_columns = self.table.columns.len();
+        let titles: Vec<(String, Option<String>)> = self
+            .table
+            .columns
+            .iter()
+            .map(|x| (x.name.clone(), x.description.clone()))
+            .collect();
+        let links: Vec<bool> = self.table.columns.iter().map(|x| x.links).collect();
+
+        let mut builder = TableBuilder::new(ui);
+        if let Some(row) = self.scroll_to.take() {
+            builder = builder.scroll_to_row(row, Some(egui::Align::Center));
+        }
+        builder
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(
+                Column::initial(50.0)
+                    .at_least(20.0)
+                    .resizable(true)
+                    .clip(true),
+            )
+            .columns(
+                Column::initial(120.0)
+                    .at_least(40.0)
+                    .resizable(true)
+                    .clip(true),
+                n_columns,
+            )
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("id");
+                });
+                for (name, description) in &titles {
+                    header.col(|ui| {
+                        let label = ui.strong(name);
+                        if let Some(description) = description {
+                            label.on_hover_text(description);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(text_height, self.table.n_rows(), |row_index, mut row| {
+                    let offset = row_index % CHUNK_ROWS;
+                    let file = self.file.clone();
+                    let chunk = self.chunk(row_index);
+                    let id = match chunk.ids.as_ref().map(|x| x.get(offset)) {
+                        Ok(Some(id)) => id.to_string(),
+                        _ => "?".to_string(),
+                    };
+                    let cells: Vec<Cell> = chunk
+                        .columns
+                        .iter()
+                        .map(|x| match x {
+                            Ok(cells) => cells
+                                .get(offset)
+                                .cloned()
+                                .unwrap_or(Cell::Text(String::new())),
+                            Err(e) => Cell::Text(format!("⚠ {e}")),
+                        })
+                        .collect();
+                    row.col(|ui| {
+                        match self.selected_row == Some(row_index) {
+                            true => ui.strong(id),
+                            false => ui.label(id),
+                        };
+                    });
+                    for (column, cell) in cells.into_iter().enumerate() {
+                        row.col(|ui| match cell {
+                            Cell::Rows(rows) => {
+                                let region = self.table.columns[column].region.as_ref().unwrap();
+                                for row in rows {
+                                    let label = region.label(row);
+                                    navigation::row_link_ui(ui, &file, &region.table, row, &label);
+                                }
+                            }
+                            Cell::Text(text) if links[column] => {
+                                navigation::link_ui(ui, &file, &text);
+                            }
+                            Cell::Text(text) => {
+                                if table::cell_ui(ui, &text) {
+                                    let title = format!("Row {row_index}, {}", titles[column].0);
+                                    self.expanded_cell = Some((title, text));
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+    }
+}
+
+impl Show for DynamicTableWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(&self.table.name)
+            .open(open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for DynamicTableWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(description) = &self.table.description {
+            ui.label(description);
+        }
+        ui.label(format!(
+            "{} rows, {} columns",
+            self.table.n_rows(),
+            self.table.columns.len()
+        ));
+        table::expanded_cell_ui(ui, &mut self.expanded_cell);
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            self.table_ui(ui);
+        });
+    }
+}
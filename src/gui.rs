@@ -1,11 +1,14 @@
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::display_traits::Show;
 use crate::gui::egui::Ui;
 use crate::hdf;
+use crate::job;
+use crate::navigation;
+use crate::spec;
 use eframe::egui;
 use eframe::egui::RichText;
 
@@ -13,6 +16,18 @@ use eframe::egui::RichText;
 pub(crate) struct NWBView {
     pub loaded_files: Vec<hdf::FileTree>,
     pub open_windows: HashMap<String, Box<dyn Show>>,
+    /// Object to expand the tree to and to open, e.g. after a reference was clicked
+    pub reveal: Option<navigation::Target>,
+    /// Windows that do not belong to a row of the tree, e.g. type specifications
+    pub side_windows: HashMap<String, Box<dyn Show>>,
+    /// Files being read in the background, with their canonical path
+    pub loading_files: Vec<(PathBuf, job::Job<hdf::FileTree>)>,
+    /// Navigation requests waiting for their file to be loaded
+    pub pending_targets: Vec<navigation::Target>,
+    /// Dataset whose window scrolls to a row once it is built, e.g. after a region was clicked
+    pub dataset_row: Option<(String, usize)>,
+    /// Targets of the external links found so far, by file and path of the link
+    pub external_targets: HashMap<String, Option<(String, String)>>,
 }
 
 impl NWBView {
@@ -34,97 +49,361 @@ impl NWBView {
                 return;
             }
         }
-        match hdf::read_nwb_file(&path) {
-            None => println!("Could not load {path}"),
-            Some(i) => self.loaded_files.push(i),
+        if self.loading_files.iter().any(|x| x.0 == actual_path) {
+            println!("The file '{path}' is already being loaded!");
+            return;
+        }
+        let title = format!("Loading {path}");
+        let loading = job::Job::spawn(title, move |progress| {
+            hdf::read_nwb_file_with(&path, progress).ok_or(format!("Could not load {path}"))
+        });
+        self.loading_files.push((actual_path, loading));
+    }
+
+    /// Load a file, e.g. given on the command line, and reveal one of its objects
+    pub(crate) fn open_file(&mut self, file: String, object: Option<String>) {
+        match object {
+            Some(path) => self.navigate(navigation::Target {
+                file,
+                path,
+                row: None,
+            }),
+            None => self.add_file(file),
+        }
+    }
+
+    /// Add the files read in the background to the tree, then resolve the navigation requests
+    /// waiting for them
+    fn receive_files(&mut self) {
+        let mut finished = Vec::new();
+        self.loading_files
+            .retain(|(path, loading)| match loading.poll() {
+                job::Status::Running => true,
+                job::Status::Done(x) => {
+                    self.loaded_files.push(x);
+                    finished.push(path.clone());
+                    false
+                }
+                job::Status::Failed(e) => {
+                    println!("{e}");
+                    finished.push(path.clone());
+                    false
+                }
+                job::Status::Cancelled => {
+                    finished.push(path.clone());
+                    false
+                }
+            });
+        if finished.is_empty() {
+            return;
+        }
+        let (ready, waiting): (Vec<_>, Vec<_>) = mem::take(&mut self.pending_targets)
+            .into_iter()
+            .partition(|x| {
+                Path::new(&x.file)
+                    .canonicalize()
+                    .is_ok_and(|path| finished.contains(&path))
+            });
+        self.pending_targets = waiting;
+        for target in ready {
+            match self.loaded_file_name(&target.file) {
+                Some(_) => self.navigate(target),
+                None => println!("Could not open {} in {}", target.path, target.file),
+            }
+        }
+    }
+
+    /// Reveal the target, its file is loaded first if needed, e.g. for external links
+    fn navigate(&mut self, mut target: navigation::Target) {
+        if self.loaded_file_name(&target.file).is_none() {
+            self.add_file(target.file.clone());
+            let loading = Path::new(&target.file)
+                .canonicalize()
+                .is_ok_and(|path| self.loading_files.iter().any(|x| x.0 == path));
+            if loading {
+                self.pending_targets.push(target);
+                return;
+            }
+        }
+        if let Some(file) = self.loaded_file_name(&target.file) {
+            target.file = file;
+        }
+        let found = self
+            .loaded_files
+            .iter()
+            .any(|x| x.file.filename() == target.file && contains_path(&x.tree, &target.path));
+        match found {
+            true => {
+                if let Some(row) = target.row {
+                    self.open_table_at(&target.file, &target.path, row);
+                }
+                self.reveal = Some(target);
+            }
+            false => println!("Could not find {} in {}", target.path, target.file),
+        }
+    }
+
+    /// Open the window of a table or a dataset scrolled to one of its rows, replacing the open
+    /// one if any
+    fn open_table_at(&mut self, file: &str, path: &str, row: usize) {
+        let Some(file) = self.loaded_files.iter().find(|x| x.file.filename() == file) else {
+            return;
+        };
+        if file.file.dataset(path).is_ok() {
+            // The window is built again when the revealed dataset is shown
+            self.open_windows.remove(path);
+            self.dataset_row = Some((path.to_string(), row));
+            return;
+        }
+        let Ok(group) = file.file.group(path) else {
+            return;
+        };
+        match super::dynamic_table_window::DynamicTableWindow::new(&group) {
+            Err(e) => println!("Could not open the table {path}: {e}"),
+            Ok(mut window) => {
+                window.scroll_to_row(row);
+                self.open_windows
+                    .insert(format!("{path}#table"), Box::new(window));
+            }
         }
     }
 }
 
 impl NWBView {
-    fn create_group_recursion(&mut self, group: &hdf::GroupTree, ui: &mut Ui, ctx: &egui::Context) {
+    /// Name of the loaded file stored at the given path, if any
+    fn loaded_file_name(&self, path: &str) -> Option<String> {
+        let path = Path::new(path).canonicalize().ok()?;
+        self.loaded_files
+            .iter()
+            .map(|x| x.file.filename())
+            .find(|x| Path::new(x).canonicalize().is_ok_and(|x| x == path))
+    }
+
+    fn open_type_window(
+        &mut self,
+        file_name: &str,
+        registry: &spec::Registry,
+        neurodata_type: &hdf::NeurodataType,
+    ) {
+        let key = format!("{file_name}#{neurodata_type}");
+        if self.side_windows.contains_key(&key) {
+            return;
+        }
+        match super::type_window::TypeWindow::new(registry, neurodata_type) {
+            None => println!("The type {neurodata_type} is not described by the file"),
+            Some(x) => {
+                self.side_windows.insert(key, Box::new(x));
+            }
+        }
+    }
+
+    fn create_group_recursion(
+        &mut self,
+        group: &hdf::GroupTree,
+        root: &hdf::GroupTree,
+        registry: &spec::Registry,
+        ui: &mut Ui,
+        ctx: &egui::Context,
+    ) {
         let group_name = group.handler.name();
+        let file_name = group.handler.filename();
         let group_split_name: Vec<&str> = group_name.split('/').collect();
-        ui.collapsing(*group_split_name.last().unwrap(), |ui| {
+        let reveal = self.reveal.as_ref().filter(|x| x.file == file_name);
+        let title = match &group.neurodata_type {
+            None => group_split_name.last().unwrap().to_string(),
+            Some(x) => format!(
+                "{} {}  [{}]",
+                type_icon(registry, x),
+                group_split_name.last().unwrap(),
+                x.name
+            ),
+        };
+        let header =
+            egui::CollapsingHeader::new(title).id_source(format!("{file_name}#{group_name}"));
+        let header = match reveal {
+            Some(x) if x.path == group_name || x.path.starts_with(&format!("{group_name}/")) => {
+                header.open(Some(true))
+            }
+            _ => header,
+        };
+        let response = header.show(ui, |ui| {
+            attributes_ui(ui, &file_name, &group_name, &group.attributes);
+
             let subgroups = &group.groups;
             if !subgroups.is_empty() {
                 for subgroup in subgroups {
-                    self.create_group_recursion(subgroup, ui, ctx);
+                    self.create_group_recursion(subgroup, root, registry, ui, ctx);
                 }
             }
 
+            for link in &group.links {
+                link_row_ui(
+                    ui,
+                    &file_name,
+                    &group.handler,
+                    link,
+                    &mut self.external_targets,
+                );
+            }
+
             let datasets = &group.datasets;
             let mut dataset_names: BTreeSet<String> = BTreeSet::default();
             if !datasets.is_empty() {
-                for dataset in datasets {
+                for dataset_tree in datasets {
+                    let dataset = &dataset_tree.name;
                     let split_name: Vec<&str> = dataset.split('/').collect();
                     let dataset_name = split_name.last().unwrap();
                     let mut is_open = self.open_windows.contains_key(dataset);
+                    let revealed = self
+                        .reveal
+                        .as_ref()
+                        .is_some_and(|x| x.file == file_name && x.path == *dataset);
                     dataset_names.insert(dataset_name.to_string());
-                    ui.horizontal(|horizontal_ui| {
+                    let row = ui.horizontal(|horizontal_ui| {
                         horizontal_ui.monospace(dataset_name.to_string());
+                        if let Some(x) = &dataset_tree.neurodata_type {
+                            let label = egui::Label::new(format!(
+                                "{} [{}]",
+                                type_icon(registry, x),
+                                x.name
+                            ))
+                            .sense(egui::Sense::click());
+                            if horizontal_ui
+                                .add(label)
+                                .on_hover_text(type_tooltip(registry, x))
+                                .clicked()
+                            {
+                                self.open_type_window(&file_name, registry, x);
+                            }
+                        }
                         if !is_open && horizontal_ui.button(RichText::new("☰")).clicked() {
                             is_open = true;
                         } else if is_open && horizontal_ui.button(RichText::new("❌")).clicked() {
                             is_open = false;
                         };
                     });
+                    if revealed {
+                        row.response.scroll_to_me(Some(egui::Align::Center));
+                        is_open = true;
+                        self.reveal = None;
+                    }
+                    attributes_ui(ui, &file_name, dataset, &dataset_tree.attributes);
                     if is_open {
                         if !self.open_windows.contains_key(dataset) {
-                            let ds = match group.handler.dataset(dataset_name.as_ref()) {
-                                Err(e) => {
-                                    self.popup(&e.to_string(), ctx, dataset, &mut is_open);
-                                    continue;
-                                }
-                                Ok(x) => x,
-                            };
-                            let ds_type = ds.dtype().unwrap();
-                            let type_descriptor = ds_type.to_descriptor();
-
-                            match type_descriptor {
-                                Err(e) => {
-                                    self.popup(&e.to_string(), ctx, dataset, &mut is_open);
-                                    continue;
-                                }
-                                Ok(descriptor) => match descriptor {
-                                    hdf5::types::TypeDescriptor::Float(_) => {
-                                        self.build_dataset::<f64>(&ds, dataset);
-                                    }
-                                    hdf5::types::TypeDescriptor::VarLenUnicode => {
-                                        self.build_dataset::<hdf5::types::VarLenUnicode>(
-                                            &ds, dataset,
-                                        );
-                                    }
-                                    hdf5::types::TypeDescriptor::Integer(_) => {
-                                        self.build_dataset::<i64>(&ds, dataset);
-                                    }
-                                    hdf5::types::TypeDescriptor::Unsigned(_) => {
-                                        self.build_dataset::<u64>(&ds, dataset);
-                                    }
-                                    hdf5::types::TypeDescriptor::Boolean => {
-                                        self.build_dataset::<bool>(&ds, dataset);
-                                    }
-                                    // hdf5::types::TypeDescriptor::Enum(_) => todo!(),
-                                    // hdf5::types::TypeDescriptor::Compound(_) => todo!(),
-                                    // hdf5::types::TypeDescriptor::FixedArray(_, _) => todo!(),
-                                    // hdf5::types::TypeDescriptor::FixedAscii(_) => todo!(),
-                                    // hdf5::types::TypeDescriptor::FixedUnicode(_) => todo!(),
-                                    // hdf5::types::TypeDescriptor::VarLenArray(_) => todo!(),
-                                    hdf5::types::TypeDescriptor::VarLenAscii => {
-                                        self.build_dataset::<hdf5::types::VarLenAscii>(
-                                            &ds, dataset,
-                                        );
+                            if let Some(column) = super::dynamic_table::Column::open(
+                                &group.handler,
+                                dataset_name.to_string(),
+                            )
+                            .filter(|x| !x.indices.is_empty())
+                            {
+                                self.build_ragged_dataset(dataset, column);
+                            } else {
+                                let ds = match group.handler.dataset(dataset_name.as_ref()) {
+                                    Err(e) => {
+                                        self.popup(&e.to_string(), ctx, dataset, &mut is_open);
+                                        continue;
                                     }
-                                    _ => {
-                                        self.popup(
-                                            "The dataset type is not supported yet.",
-                                            ctx,
-                                            dataset,
-                                            &mut is_open,
-                                        );
+                                    Ok(x) => x,
+                                };
+                                let ds_type = ds.dtype().unwrap();
+                                let type_descriptor = ds_type.to_descriptor();
+
+                                match type_descriptor {
+                                    Err(e) => {
+                                        self.popup(&e.to_string(), ctx, dataset, &mut is_open);
                                         continue;
                                     }
-                                },
+                                    Ok(descriptor) => match descriptor {
+                                        hdf5::types::TypeDescriptor::Float(_) => {
+                                            self.build_dataset::<f64>(&ds, dataset);
+                                        }
+                                        hdf5::types::TypeDescriptor::VarLenUnicode => {
+                                            self.build_dataset::<hdf5::types::VarLenUnicode>(
+                                                &ds, dataset,
+                                            );
+                                        }
+                                        hdf5::types::TypeDescriptor::Integer(_) => {
+                                            match super::dynamic_table::Region::open(&ds) {
+                                                Some(region) => {
+                                                    self.build_region_dataset(&ds, dataset, region)
+                                                }
+                                                None => self.build_dataset::<i64>(&ds, dataset),
+                                            }
+                                        }
+                                        hdf5::types::TypeDescriptor::Unsigned(_) => {
+                                            match super::dynamic_table::Region::open(&ds) {
+                                                Some(region) => {
+                                                    self.build_region_dataset(&ds, dataset, region)
+                                                }
+                                                None => self.build_dataset::<u64>(&ds, dataset),
+                                            }
+                                        }
+                                        hdf5::types::TypeDescriptor::Boolean => {
+                                            self.build_dataset::<bool>(&ds, dataset);
+                                        }
+                                        hdf5::types::TypeDescriptor::Compound(_)
+                                        | hdf5::types::TypeDescriptor::FixedArray(..)
+                                        | hdf5::types::TypeDescriptor::FixedAscii(_)
+                                        | hdf5::types::TypeDescriptor::FixedUnicode(_)
+                                        | hdf5::types::TypeDescriptor::Reference(
+                                            hdf5::types::Reference::Region,
+                                        ) => {
+                                            self.build_value_dataset(&ds, dataset, &descriptor);
+                                        }
+                                        hdf5::types::TypeDescriptor::Enum(enum_type) => {
+                                            let new_ds = super::table::TableWindow::with_format(
+                                                vec!["Name".to_string(), "Value".to_string()],
+                                                move |x: &i64| {
+                                                    let name =
+                                                        hdf::enum_member_name(&enum_type, *x);
+                                                    vec![
+                                                        name.unwrap_or("?").to_string(),
+                                                        x.to_string(),
+                                                    ]
+                                                },
+                                            );
+                                            self.build_table(&ds, dataset, new_ds);
+                                        }
+                                        hdf5::types::TypeDescriptor::VarLenArray(base)
+                                            if is_numeric(&base) =>
+                                        {
+                                            let new_ds = super::table::TableWindow::with_format(
+                                                vec![],
+                                                |x: &hdf5::types::VarLenArray<f64>| {
+                                                    vec![super::value::format_array(x.as_slice())]
+                                                },
+                                            );
+                                            self.build_table(&ds, dataset, new_ds);
+                                        }
+                                        hdf5::types::TypeDescriptor::Reference(
+                                            hdf5::types::Reference::Object,
+                                        ) => {
+                                            let location = ds.clone();
+                                            let mut new_ds = super::table::TableWindow::with_format(
+                                                vec![],
+                                                move |x: &hdf5::ObjectReference1| {
+                                                    vec![hdf::reference_path(&location, x)]
+                                                },
+                                            );
+                                            new_ds.set_links(file_name.clone(), vec![0]);
+                                            self.build_table(&ds, dataset, new_ds);
+                                        }
+                                        hdf5::types::TypeDescriptor::VarLenAscii => {
+                                            self.build_dataset::<hdf5::types::VarLenAscii>(
+                                                &ds, dataset,
+                                            );
+                                        }
+                                        _ => {
+                                            self.popup(
+                                                "The dataset type is not supported yet.",
+                                                ctx,
+                                                dataset,
+                                                &mut is_open,
+                                            );
+                                            continue;
+                                        }
+                                    },
+                                }
                             }
                         }
                         self.open_windows
@@ -134,6 +413,68 @@ impl NWBView {
                     }
                     self.check_close(is_open, dataset);
                 }
+                if super::dynamic_table::is_dynamic_table(group, registry) {
+                    let key = format!("{group_name}#table");
+                    let mut is_open = self.open_windows.contains_key(&key);
+                    if ui.button(RichText::new(" 📋 Table")).clicked() {
+                        is_open = true;
+                    }
+                    if is_open {
+                        if !self.open_windows.contains_key(&key) {
+                            match super::dynamic_table_window::DynamicTableWindow::new(
+                                &group.handler,
+                            ) {
+                                Ok(x) => {
+                                    self.open_windows.insert(key.clone(), Box::new(x));
+                                }
+                                Err(e) => self.popup(&e, ctx, &key, &mut is_open),
+                            }
+                        }
+                        self.open_windows
+                            .get_mut(&key)
+                            .unwrap()
+                            .show(ctx, &mut is_open);
+                    }
+                    self.check_close(is_open, &key);
+                }
+                if dataset_names.contains("spike_times")
+                    && super::dynamic_table::is_dynamic_table(group, registry)
+                {
+                    let key = format!("{group_name}#raster");
+                    let mut is_open = self.open_windows.contains_key(&key);
+                    if ui.button(RichText::new(" ⋮ Raster")).clicked() {
+                        is_open = true;
+                    }
+                    if is_open {
+                        if !self.open_windows.contains_key(&key) {
+                            let raster = super::raster::RasterWindow::new(&group.handler);
+                            self.open_windows.insert(key.clone(), Box::new(raster));
+                        }
+                        self.open_windows
+                            .get_mut(&key)
+                            .unwrap()
+                            .show(ctx, &mut is_open);
+                    }
+                    self.check_close(is_open, &key);
+
+                    let key = format!("{group_name}#psth");
+                    let mut is_open = self.open_windows.contains_key(&key);
+                    if ui.button(RichText::new(" 📊 PSTH")).clicked() {
+                        is_open = true;
+                    }
+                    if is_open {
+                        if !self.open_windows.contains_key(&key) {
+                            let tables = super::spikes::interval_tables(root, registry);
+                            let psth = super::psth_window::PsthWindow::new(&group.handler, tables);
+                            self.open_windows.insert(key.clone(), Box::new(psth));
+                        }
+                        self.open_windows
+                            .get_mut(&key)
+                            .unwrap()
+                            .show(ctx, &mut is_open);
+                    }
+                    self.check_close(is_open, &key);
+                }
                 if dataset_names.contains("data") {
                     let mut is_open = self.open_windows.contains_key(&group_name);
                     if ui.button(RichText::new(" 🗠 Plot")).clicked() {
@@ -154,6 +495,28 @@ impl NWBView {
                 }
             }
         });
+        if let Some(x) = &group.neurodata_type {
+            response
+                .header_response
+                .clone()
+                .on_hover_text(type_tooltip(registry, x))
+                .context_menu(|ui| {
+                    if ui.button("Show the type specification").clicked() {
+                        self.open_type_window(&file_name, registry, x);
+                        ui.close_menu();
+                    }
+                });
+        }
+        let revealed = self
+            .reveal
+            .as_ref()
+            .is_some_and(|x| x.file == file_name && x.path == group_name);
+        if revealed {
+            response
+                .header_response
+                .scroll_to_me(Some(egui::Align::Center));
+            self.reveal = None;
+        }
     }
 
     fn build_dataset<T: hdf5::H5Type + std::fmt::Display>(
@@ -161,16 +524,79 @@ impl NWBView {
         ds: &hdf5::Dataset,
         dataset: &str,
     ) {
-        let mut new_ds = Box::<super::table::TableWindow<T>>::default();
+        self.build_table(ds, dataset, super::table::TableWindow::<T>::default());
+    }
+
+    fn build_table<T: 'static>(
+        &mut self,
+        ds: &hdf5::Dataset,
+        dataset: &str,
+        mut new_ds: super::table::TableWindow<T>,
+    ) {
         new_ds.set_name(dataset.to_owned());
+        if let Some((_, row)) = self.dataset_row.take_if(|x| x.0 == dataset) {
+            new_ds.scroll_to_row(row);
+        }
         if ds.is_scalar() {
-            let scalar: String = ds.read_scalar::<T>().unwrap().to_string();
+            let scalar = new_ds.read_scalar(ds).unwrap_or_else(|e| format!("⚠ {e}"));
             new_ds.set_scalar(scalar);
         } else {
-            let x_data: Vec<T> = ds.read_raw::<T>().unwrap();
-            new_ds.set_data(x_data);
+            new_ds.set_dataset(ds.clone());
         }
-        self.open_windows.insert(dataset.to_string(), new_ds);
+        self.open_windows
+            .insert(dataset.to_string(), Box::new(new_ds));
+    }
+
+    /// Row indices of a DynamicTableRegion are shown with the labels of the selected rows
+    fn build_region_dataset(
+        &mut self,
+        ds: &hdf5::Dataset,
+        dataset: &str,
+        region: super::dynamic_table::Region,
+    ) {
+        let table = region.table.clone();
+        let mut new_ds = super::table::TableWindow::with_format(
+            vec!["Row".to_string(), "Label".to_string()],
+            move |x: &i64| vec![x.to_string(), region.label(*x)],
+        );
+        new_ds.set_row_links(ds.filename(), table, 0);
+        self.build_table(ds, dataset, new_ds);
+    }
+
+    /// Ragged datasets are split into the rows of their VectorIndex, as in a DynamicTable
+    fn build_ragged_dataset(&mut self, dataset: &str, column: super::dynamic_table::Column) {
+        let index = column.indices[0].clone();
+        let new_ds = super::table::TableWindow::with_reader(
+            vec![],
+            move |_, rows, _| super::dynamic_table::read_column(&column, rows),
+            |x: &String| vec![x.clone()],
+        );
+        self.build_table(&index, dataset, new_ds);
+    }
+
+    /// Elements decoded from the type stored in the file, with one column per compound field
+    fn build_value_dataset(
+        &mut self,
+        ds: &hdf5::Dataset,
+        dataset: &str,
+        descriptor: &hdf5::types::TypeDescriptor,
+    ) {
+        let fields = super::value::fields(descriptor);
+        let names = match descriptor {
+            hdf5::types::TypeDescriptor::Compound(_) => fields.into_iter().map(|x| x.0).collect(),
+            _ => vec![],
+        };
+        let mut new_ds = super::table::TableWindow::with_reader(
+            names,
+            |ds, rows, slice_indices| {
+                super::value::read(ds, super::table::selection(ds, rows, slice_indices))
+            },
+            |x: &super::value::Value| x.leaves().iter().map(|x| x.to_string()).collect(),
+        );
+        new_ds.set_targets(ds.filename(), |x: &super::value::Value| {
+            x.leaves().iter().map(|x| x.target()).collect()
+        });
+        self.build_table(ds, dataset, new_ds);
     }
 
     fn popup(&mut self, msg: &str, ctx: &egui::Context, dataset: &str, is_open: &mut bool) {
@@ -193,6 +619,11 @@ impl NWBView {
 
 impl eframe::App for NWBView {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.receive_files();
+        if let Some(target) = navigation::take_request(ctx) {
+            self.navigate(target);
+        }
+
         egui::TopBottomPanel::bottom("theme_panel")
             .resizable(false)
             .min_height(32.0)
@@ -212,6 +643,9 @@ impl eframe::App for NWBView {
             {
                 self.add_file(path.display().to_string());
             }
+            for (_, loading) in &self.loading_files {
+                loading.ui(ui);
+            }
 
             let mut all_loaded_files: Vec<hdf::FileTree> = Vec::new();
             mem::swap(&mut all_loaded_files, &mut self.loaded_files);
@@ -222,11 +656,71 @@ impl eframe::App for NWBView {
                         if horizontal_ui.button(RichText::new("❌")).clicked() {
                             loaded_file.is_opened = false; // Mark the file as closed
                         };
-                        horizontal_ui.collapsing(loaded_file.file.filename(), |header_ui| {
+                        if horizontal_ui
+                            .button(RichText::new("✔"))
+                            .on_hover_text("Validate the file against its specification")
+                            .clicked()
+                        {
+                            let tree = loaded_file.tree.clone();
+                            let registry = loaded_file.specification.clone();
+                            let validating =
+                                job::Job::spawn("Validating the file", move |progress| {
+                                    Ok(super::validate::validate_with(&tree, &registry, progress))
+                                });
+                            let filename = loaded_file.file.filename();
+                            let window = super::validation_window::ValidationWindow::new(
+                                filename.clone(),
+                                validating,
+                            );
+                            self.side_windows
+                                .insert(format!("{filename}#validation"), Box::new(window));
+                        }
+                        if horizontal_ui
+                            .button(RichText::new("🔍"))
+                            .on_hover_text("Inspect the file for best practices")
+                            .clicked()
+                        {
+                            let tree = loaded_file.tree.clone();
+                            let inspecting =
+                                job::Job::spawn("Inspecting the file", move |progress| {
+                                    let checks = super::inspect::default_checks();
+                                    Ok(super::inspect::inspect_with(&tree, &checks, progress))
+                                });
+                            let filename = loaded_file.file.filename();
+                            let window = super::inspection_window::InspectionWindow::new(
+                                filename.clone(),
+                                inspecting,
+                            );
+                            self.side_windows
+                                .insert(format!("{filename}#inspection"), Box::new(window));
+                        }
+                        let filename = loaded_file.file.filename();
+                        let header = egui::CollapsingHeader::new(&filename).id_source(&filename);
+                        let header = match &self.reveal {
+                            Some(x) if x.file == filename => header.open(Some(true)),
+                            _ => header,
+                        };
+                        let response = header.show(horizontal_ui, |header_ui| {
                             for groups in &loaded_file.tree.groups {
-                                self.create_group_recursion(groups, header_ui, ctx);
+                                self.create_group_recursion(
+                                    groups,
+                                    &loaded_file.tree,
+                                    &loaded_file.specification,
+                                    header_ui,
+                                    ctx,
+                                );
                             }
                         });
+                        if self
+                            .reveal
+                            .as_ref()
+                            .is_some_and(|x| x.file == filename && x.path == "/")
+                        {
+                            response
+                                .header_response
+                                .scroll_to_me(Some(egui::Align::Center));
+                            self.reveal = None;
+                        }
                     });
                 }
             });
@@ -235,6 +729,12 @@ impl eframe::App for NWBView {
             mem::swap(&mut all_loaded_files, &mut self.loaded_files);
         });
 
+        self.side_windows.retain(|_, window| {
+            let mut is_open = true;
+            window.show(ctx, &mut is_open);
+            is_open
+        });
+
         preview_files_being_dropped(ctx);
 
         // Collect dropped files:
@@ -251,6 +751,158 @@ impl eframe::App for NWBView {
     }
 }
 
+/// Whether the values of the type can be read as floating point numbers
+fn is_numeric(descriptor: &hdf5::types::TypeDescriptor) -> bool {
+    matches!(
+        descriptor,
+        hdf5::types::TypeDescriptor::Float(_)
+            | hdf5::types::TypeDescriptor::Integer(_)
+            | hdf5::types::TypeDescriptor::Unsigned(_)
+    )
+}
+
+/// Whether the tree holds a group or a dataset at the given path
+fn contains_path(group: &hdf::GroupTree, path: &str) -> bool {
+    group.handler.name() == path
+        || group.datasets.iter().any(|x| x.name == path)
+        || group.groups.iter().any(|x| contains_path(x, path))
+}
+
+/// Icon of the closest family the type or one of its base types belongs to
+fn type_icon(registry: &spec::Registry, neurodata_type: &hdf::NeurodataType) -> &'static str {
+    let mut names = vec![neurodata_type.name.as_str()];
+    names.extend(
+        registry
+            .ancestors(neurodata_type)
+            .iter()
+            .map(|x| x.name.as_str()),
+    );
+    names.into_iter().find_map(family_icon).unwrap_or("🏷")
+}
+
+/// Icon of the family of a neurodata type, guessed from the naming conventions of NWB
+fn family_icon(neurodata_type: &str) -> Option<&'static str> {
+    let icon = match neurodata_type {
+        x if x.starts_with("Image") || x.ends_with("ImageSeries") || x == "TwoPhotonSeries" => "🖼",
+        x if x.ends_with("Series") => "🗠",
+        x if x.ends_with("Table") || x == "TimeIntervals" || x == "Units" => "📋",
+        x if x.starts_with("Vector") || x.ends_with("Data") || x.ends_with("Region") => "☰",
+        "ProcessingModule" => "⚙",
+        "Device" | "ElectrodeGroup" | "ImagingPlane" | "OptogeneticStimulusSite" => "🔌",
+        "Subject" | "LabMetaData" => "ℹ",
+        _ => return None,
+    };
+    Some(icon)
+}
+
+/// Full name of a neurodata type and its description from the cached specification
+fn type_tooltip(registry: &spec::Registry, neurodata_type: &hdf::NeurodataType) -> String {
+    let ancestors = registry.ancestors(neurodata_type);
+    let Some(spec) = ancestors.first() else {
+        return format!("{neurodata_type}\n\nNot described by the cached specification");
+    };
+    let names: Vec<&str> = ancestors.iter().map(|x| x.name.as_str()).collect();
+    format!(
+        "{}.{}\n{}\n\n{}",
+        spec.namespace,
+        spec.name,
+        names.join(" → "),
+        spec.doc
+    )
+}
+
+/// Name and target of a link, clicking the target reveals it, loading its file if needed. The
+/// targets of external links are found once asked for and kept in `external_targets`.
+fn link_row_ui(
+    ui: &mut Ui,
+    file: &str,
+    group: &hdf5::Group,
+    link: &hdf::LinkTree,
+    external_targets: &mut HashMap<String, Option<(String, String)>>,
+) {
+    let name = hdf::last_component(&link.name);
+    let kind = match link.kind {
+        hdf5::LinkType::Hard => "Hard link to an object shown elsewhere in the tree",
+        hdf5::LinkType::Soft => "Soft link",
+        hdf5::LinkType::External => "External link",
+    };
+    ui.horizontal(|ui| {
+        let icon = match link.kind {
+            hdf5::LinkType::External => "🗁",
+            _ => "🔗",
+        };
+        ui.label(icon).on_hover_text(kind);
+        ui.monospace(name);
+        ui.label("→");
+        let target = match link.kind {
+            hdf5::LinkType::External => {
+                let key = format!("{file}:{}", link.name);
+                let Some(target) = external_targets.get(&key) else {
+                    if ui
+                        .link("find the target")
+                        .on_hover_text("Open the external file to find the target of the link")
+                        .clicked()
+                    {
+                        external_targets.insert(key, hdf::external_target(group, link));
+                    }
+                    return;
+                };
+                target.clone().map(|(target, file)| (Some(file), target))
+            }
+            _ => link.target.clone().map(|target| (None, target)),
+        };
+        match target {
+            None => {
+                ui.weak("dangling");
+            }
+            Some((None, target)) => navigation::link_ui(ui, file, &target),
+            Some((Some(target_file), target)) => {
+                if ui
+                    .link(format!("{target_file}:{target}"))
+                    .on_hover_text("Open the file and go to the object")
+                    .clicked()
+                {
+                    navigation::request(
+                        ui.ctx(),
+                        navigation::Target {
+                            file: target_file,
+                            path: target,
+                            row: None,
+                        },
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Collapsible list of the attributes of a group or a dataset
+fn attributes_ui(ui: &mut Ui, file: &str, path: &str, attributes: &[hdf::Attribute]) {
+    if attributes.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(RichText::new("Attributes").italics())
+        .id_source(format!("{path}#attributes"))
+        .show(ui, |ui| {
+            egui::Grid::new(format!("{path}#attributes_grid"))
+                .striped(true)
+                .show(ui, |ui| {
+                    for attribute in attributes {
+                        ui.monospace(&attribute.name);
+                        match &attribute.value {
+                            hdf::AttributeValue::Reference(target) => {
+                                navigation::link_ui(ui, file, target);
+                            }
+                            value => {
+                                ui.label(value.to_string());
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
 /// Preview hovering files:
 fn preview_files_being_dropped(ctx: &egui::Context) {
     use egui::*;
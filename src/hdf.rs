@@ -1,48 +1,477 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::job;
+use crate::spec;
+use crate::value;
 use hdf5::File;
+use hdf5::types::{EnumMember, EnumType, TypeDescriptor, VarLenAscii, VarLenUnicode};
 
 pub struct GroupTree {
     pub handler: hdf5::Group,
     pub groups: Vec<GroupTree>,
-    pub datasets: Vec<String>,
+    pub datasets: Vec<DatasetTree>,
+    pub links: Vec<LinkTree>,
+    pub attributes: Vec<Attribute>,
+    pub neurodata_type: Option<NeurodataType>,
+}
+
+impl GroupTree {
+    /// Number of groups of the tree, the group itself included
+    pub fn n_groups(&self) -> usize {
+        1 + self.groups.iter().map(GroupTree::n_groups).sum::<usize>()
+    }
+}
+
+pub struct DatasetTree {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub neurodata_type: Option<NeurodataType>,
+}
+
+/// NWB type of a group or a dataset, read from its `neurodata_type` and `namespace` attributes
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeurodataType {
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+impl fmt::Display for NeurodataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.namespace {
+            None => write!(f, "{}", self.name),
+            Some(namespace) => write!(f, "{namespace}.{}", self.name),
+        }
+    }
 }
 
+/// A link that is not walked into: a soft link, an external link or an additional hard link
+/// to an object already present in the tree
+pub struct LinkTree {
+    pub name: String,
+    pub kind: hdf5::LinkType,
+    /// Path of the target object, `None` if the link is dangling. External links are not
+    /// followed while building the tree, their target is found by `external_target`.
+    pub target: Option<String>,
+}
+
+/// A file read into a tree. The tree and the specification are shared with the jobs checking
+/// the file in the background.
 pub struct FileTree {
     pub file: hdf5::File,
-    pub tree: GroupTree,
+    pub tree: Arc<GroupTree>,
+    pub specification: Arc<spec::Registry>,
     pub is_opened: bool,
 }
 
-pub(crate) fn build_tree(group: hdf5::Group) -> GroupTree {
-    let groups: Vec<hdf5::Group> = group.groups().unwrap();
-    let datasets: Vec<String> = group
-        .datasets()
-        .unwrap()
-        .into_iter()
-        .map(|x| x.name())
-        .collect();
+/// An HDF5 attribute decoded for display
+pub struct Attribute {
+    pub name: String,
+    pub value: AttributeValue,
+}
+
+pub enum AttributeValue {
+    Text(String),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<AttributeValue>),
+    /// Path of the object targeted by an object reference
+    Reference(String),
+    /// The attribute type can not be decoded, holds the type description
+    Unsupported(String),
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttributeValue::Text(x) => write!(f, "{x}"),
+            AttributeValue::Integer(x) => write!(f, "{x}"),
+            AttributeValue::Unsigned(x) => write!(f, "{x}"),
+            AttributeValue::Float(x) => write!(f, "{x}"),
+            AttributeValue::Boolean(x) => write!(f, "{x}"),
+            AttributeValue::Array(values) => {
+                let items: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            AttributeValue::Reference(x) => write!(f, "→ {x}"),
+            AttributeValue::Unsupported(x) => write!(f, "<{x}>"),
+        }
+    }
+}
+
+/// Integer value of an enum member, sign-extended for signed enums
+pub(crate) fn enum_member_value(enum_type: &EnumType, member: &EnumMember) -> i64 {
+    let unused_bits = 64 - 8 * enum_type.size as u32;
+    match enum_type.signed {
+        true => ((member.value << unused_bits) as i64) >> unused_bits,
+        false => member.value as i64,
+    }
+}
+
+/// Symbolic name of an enum value, if it is a member of the enum
+pub(crate) fn enum_member_name(enum_type: &EnumType, value: i64) -> Option<&str> {
+    enum_type
+        .members
+        .iter()
+        .find(|x| enum_member_value(enum_type, x) == value)
+        .map(|x| x.name.as_str())
+}
+
+/// Symbolic name of an enum value followed by the value itself, e.g. `LEFT (1)`
+pub(crate) fn enum_label(enum_type: &EnumType, value: i64) -> String {
+    match enum_member_name(enum_type, value) {
+        None => format!("? ({value})"),
+        Some(name) => format!("{name} ({value})"),
+    }
+}
+
+/// Build the tree of a group, following every object through its first hard link only. The
+/// objects read are counted in `progress`, once cancelled the remaining groups are left empty.
+pub(crate) fn build_tree(group: hdf5::Group, progress: &job::Progress) -> GroupTree {
+    let mut visited = HashMap::new();
+    if let Ok(info) = group.loc_info() {
+        visited.insert(object_key(info.token), group.name());
+    }
+    let mut tree = build_subtree(group, &mut visited, progress);
+    resolve_soft_links(&mut tree, &visited);
+    tree
+}
+
+/// Key of an object of the file. Location tokens cannot be hashed, their debug text holds the
+/// address of the object.
+fn object_key(token: hdf5::LocationToken) -> String {
+    format!("{token:?}")
+}
+
+/// Objects already in the tree are recorded in `visited` with their path, so that hard link
+/// cycles are not walked again. External links are recorded without opening their file.
+fn build_subtree(
+    group: hdf5::Group,
+    visited: &mut HashMap<String, String>,
+    progress: &job::Progress,
+) -> GroupTree {
+    let members = match progress.is_cancelled() {
+        true => vec![],
+        false => group
+            .iter_visit_default(vec![], |_, name, info, members| {
+                members.push((name.to_string(), info.link_type));
+                true
+            })
+            .unwrap_or_default(),
+    };
+    progress.advance(members.len());
+    let parent = group.name();
+    let mut sub_groups: Vec<hdf5::Group> = Vec::new();
+    let mut datasets: Vec<DatasetTree> = Vec::new();
+    let mut links: Vec<LinkTree> = Vec::new();
+    for (name, kind) in members {
+        let path = match parent.as_str() {
+            "/" => format!("/{name}"),
+            _ => format!("{parent}/{name}"),
+        };
+        match kind {
+            hdf5::LinkType::Soft | hdf5::LinkType::External => links.push(LinkTree {
+                name: path,
+                kind,
+                target: None,
+            }),
+            hdf5::LinkType::Hard => {
+                let Ok(info) = group.loc_info_by_name(&name) else {
+                    continue;
+                };
+                let key = object_key(info.token);
+                if let Some(first_path) = visited.get(&key) {
+                    links.push(LinkTree {
+                        name: path,
+                        kind,
+                        target: Some(first_path.clone()),
+                    });
+                    continue;
+                }
+                visited.insert(key, path);
+                match info.loc_type {
+                    hdf5::LocationType::Group => {
+                        if let Ok(x) = group.group(&name) {
+                            sub_groups.push(x);
+                        }
+                    }
+                    hdf5::LocationType::Dataset => {
+                        if let Ok(x) = group.dataset(&name) {
+                            let attributes = read_attributes(&x);
+                            datasets.push(DatasetTree {
+                                name: x.name(),
+                                neurodata_type: neurodata_type(&attributes),
+                                attributes,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
     let mut sub_trees: Vec<GroupTree> = Vec::new();
-    for sub_group in groups {
-        sub_trees.push(build_tree(sub_group));
+    for sub_group in sub_groups {
+        sub_trees.push(build_subtree(sub_group, visited, progress));
     }
+    let attributes = read_attributes(&group);
     GroupTree {
+        neurodata_type: neurodata_type(&attributes),
+        attributes,
         handler: group,
         groups: sub_trees,
         datasets,
+        links,
+    }
+}
+
+/// Text value of an attribute, if it is present and holds a string
+pub(crate) fn text_attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|x| x.name == name)
+        .and_then(|x| match &x.value {
+            AttributeValue::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+}
+
+fn neurodata_type(attributes: &[Attribute]) -> Option<NeurodataType> {
+    text_attribute(attributes, "neurodata_type").map(|name| NeurodataType {
+        name: name.to_string(),
+        namespace: text_attribute(attributes, "namespace").map(str::to_string),
+    })
+}
+
+/// Soft links may point anywhere in the file, so they are resolved once the whole tree is built
+fn resolve_soft_links(tree: &mut GroupTree, visited: &HashMap<String, String>) {
+    for link in tree.links.iter_mut() {
+        if link.kind != hdf5::LinkType::Soft {
+            continue;
+        }
+        let name = last_component(&link.name);
+        if let Ok(info) = tree.handler.loc_info_by_name(name) {
+            link.target = visited.get(&object_key(info.token)).cloned();
+        }
+    }
+    for sub_tree in tree.groups.iter_mut() {
+        resolve_soft_links(sub_tree, visited);
+    }
+}
+
+/// Path and file of the target of an external link of `group`, opening the file it points to.
+/// `None` if the link is dangling.
+pub(crate) fn external_target(group: &hdf5::Group, link: &LinkTree) -> Option<(String, String)> {
+    let name = last_component(&link.name);
+    let target = match group.loc_type_by_name(name) {
+        Ok(hdf5::LocationType::Group) => group.group(name).map(|x| (x.name(), x.filename())),
+        _ => group.dataset(name).map(|x| (x.name(), x.filename())),
+    };
+    target.ok()
+}
+
+/// Name of an object from its path, e.g. `data` for `/acquisition/lfp/data`
+pub(crate) fn last_component(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Read and decode all the attributes attached to a group or a dataset
+pub(crate) fn read_attributes(location: &hdf5::Location) -> Vec<Attribute> {
+    let names = location.attr_names().unwrap_or_default();
+    names
+        .into_iter()
+        .map(|name| {
+            let value = match location.attr(&name) {
+                Err(e) => AttributeValue::Unsupported(e.to_string()),
+                Ok(attr) => read_attribute_value(location, &attr),
+            };
+            Attribute { name, value }
+        })
+        .collect()
+}
+
+fn read_attribute_value(location: &hdf5::Location, attr: &hdf5::Attribute) -> AttributeValue {
+    let descriptor = match attr.dtype().and_then(|x| x.to_descriptor()) {
+        Err(e) => return AttributeValue::Unsupported(e.to_string()),
+        Ok(x) => x,
+    };
+    let value = match &descriptor {
+        TypeDescriptor::Integer(_) => read_values(attr, AttributeValue::Integer),
+        TypeDescriptor::Unsigned(_) => read_values(attr, AttributeValue::Unsigned),
+        TypeDescriptor::Float(_) => read_values(attr, AttributeValue::Float),
+        TypeDescriptor::Boolean => read_values(attr, AttributeValue::Boolean),
+        TypeDescriptor::Enum(enum_type) => read_values(attr, |x: i64| {
+            AttributeValue::Text(enum_label(enum_type, x))
+        }),
+        TypeDescriptor::VarLenUnicode => {
+            read_values(attr, |x: VarLenUnicode| AttributeValue::Text(x.to_string()))
+        }
+        TypeDescriptor::VarLenAscii => {
+            read_values(attr, |x: VarLenAscii| AttributeValue::Text(x.to_string()))
+        }
+        TypeDescriptor::FixedAscii(_) | TypeDescriptor::FixedUnicode(_) => {
+            value::read_attribute(attr).map(|values| {
+                let mut values: Vec<AttributeValue> = values
+                    .iter()
+                    .map(|x| AttributeValue::Text(x.to_string()))
+                    .collect();
+                match attr.is_scalar() {
+                    true => values.pop().unwrap_or(AttributeValue::Text(String::new())),
+                    false => AttributeValue::Array(values),
+                }
+            })
+        }
+        TypeDescriptor::Reference(hdf5::types::Reference::Object) => {
+            read_values(attr, |x: hdf5::ObjectReference1| {
+                AttributeValue::Reference(reference_path(location, &x))
+            })
+        }
+        _ => Ok(AttributeValue::Unsupported(descriptor.to_string())),
+    };
+    value.unwrap_or_else(|e| AttributeValue::Unsupported(e.to_string()))
+}
+
+/// Read a scalar or an array attribute, converting each element with `convert`
+fn read_values<T: hdf5::H5Type>(
+    attr: &hdf5::Attribute,
+    convert: impl Fn(T) -> AttributeValue,
+) -> hdf5::Result<AttributeValue> {
+    if attr.is_scalar() {
+        Ok(convert(attr.read_scalar::<T>()?))
+    } else {
+        let values = attr.read_raw::<T>()?;
+        Ok(AttributeValue::Array(
+            values.into_iter().map(convert).collect(),
+        ))
+    }
+}
+
+/// Read strings of any string type
+pub(crate) fn read_strings(dataset: &hdf5::Dataset) -> hdf5::Result<Vec<String>> {
+    let descriptor = dataset.dtype()?.to_descriptor()?;
+    match descriptor {
+        TypeDescriptor::VarLenUnicode => dataset
+            .read_raw::<VarLenUnicode>()
+            .map(|x| x.iter().map(|s| s.to_string()).collect()),
+        TypeDescriptor::VarLenAscii => dataset
+            .read_raw::<VarLenAscii>()
+            .map(|x| x.iter().map(|s| s.to_string()).collect()),
+        TypeDescriptor::FixedAscii(_) | TypeDescriptor::FixedUnicode(_) => {
+            value::read(dataset, hdf5::Selection::All)
+                .map(|x| x.iter().map(|s| s.to_string()).collect())
+        }
+        _ => Err(format!("{descriptor} is not a string type").into()),
+    }
+}
+
+/// Path of the object targeted by an object reference
+pub(crate) fn reference_path(
+    location: &hdf5::Location,
+    reference: &hdf5::ObjectReference1,
+) -> String {
+    match location.dereference(reference) {
+        Err(e) => format!("<invalid reference: {e}>"),
+        Ok(hdf5::ReferencedObject::Group(x)) => x.name(),
+        Ok(hdf5::ReferencedObject::Dataset(x)) => x.name(),
+        Ok(hdf5::ReferencedObject::Datatype(_)) => "<named datatype>".to_string(),
     }
 }
 
+/// Conversion of the raw values of a TimeSeries `data` into physical units
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scaling {
+    pub conversion: f64,
+    pub offset: f64,
+    pub unit: Option<String>,
+}
+
+impl Scaling {
+    /// Read the `conversion`, `offset` and `unit` attributes, missing factors are neutral
+    pub(crate) fn read(data: &hdf5::Dataset) -> Scaling {
+        let factor = |name: &str| {
+            data.attr(name)
+                .and_then(|x| x.read_scalar::<f64>())
+                .ok()
+                .filter(|x| x.is_finite())
+        };
+        let attributes = read_attributes(data);
+        Scaling {
+            conversion: factor("conversion").unwrap_or(1.0),
+            offset: factor("offset").unwrap_or(0.0),
+            unit: text_attribute(&attributes, "unit").map(str::to_string),
+        }
+    }
+
+    pub fn apply(&self, raw: f64) -> f64 {
+        raw * self.conversion + self.offset
+    }
+
+    /// Whether scaled values differ from the raw ones
+    pub fn is_identity(&self) -> bool {
+        self.conversion == 1.0 && self.offset == 0.0
+    }
+}
+
+/// Time in seconds of the samples of a TimeSeries, read from its `timestamps` or computed from
+/// its `starting_time` and `rate`. `None` if the group holds neither.
+pub(crate) fn sample_times(group: &hdf5::Group, n_samples: usize) -> Option<Vec<f64>> {
+    if let Ok(timestamps) = group.dataset("timestamps") {
+        return timestamps.read_raw::<f64>().ok();
+    }
+    let (start, rate) = sample_rate(group)?;
+    Some((0..n_samples).map(|i| start + i as f64 / rate).collect())
+}
+
+/// `starting_time` and sampling `rate` of a regularly sampled TimeSeries
+pub(crate) fn sample_rate(group: &hdf5::Group) -> Option<(f64, f64)> {
+    let starting_time = group.dataset("starting_time").ok()?;
+    let start = starting_time.read_scalar::<f64>().ok()?;
+    let rate = starting_time.attr("rate").ok()?.read_scalar::<f64>().ok()?;
+    if rate <= 0.0 || !rate.is_finite() {
+        return None;
+    }
+    Some((start, rate))
+}
+
+/// Values of an enum dataset, one channel per column of its rows
+pub(crate) fn enum_channels(data: &hdf5::Dataset) -> hdf5::Result<Vec<Vec<f64>>> {
+    let n_channels: usize = data.shape().iter().skip(1).product::<usize>().max(1);
+    let values: Vec<i64> = data.read_raw()?;
+    Ok((0..n_channels)
+        .map(|c| {
+            values
+                .iter()
+                .skip(c)
+                .step_by(n_channels)
+                .map(|x| *x as f64)
+                .collect()
+        })
+        .collect())
+}
+
 pub(crate) fn read_nwb_file(path: &str) -> Option<FileTree> {
+    read_nwb_file_with(path, &job::Progress::default())
+}
+
+/// Read the file, counting the objects read in `progress`. `None` if the file can not be read
+/// or if the reading is cancelled.
+pub(crate) fn read_nwb_file_with(path: &str, progress: &job::Progress) -> Option<FileTree> {
     let file = File::open(path).ok();
 
-    match file {
+    let tree = match file {
         None => None,
         Some(x) => match x.as_group() {
             Err(_) => None,
             Ok(y) => Some(FileTree {
                 file: x,
-                tree: build_tree(y),
+                specification: Arc::new(spec::Registry::read(&y)),
+                tree: Arc::new(build_tree(y, progress)),
                 is_opened: true,
             }),
         },
-    }
+    };
+    tree.filter(|_| !progress.is_cancelled())
 }
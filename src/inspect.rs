@@ -0,0 +1,378 @@
+use std::fmt;
+
+use crate::hdf;
+use crate::job;
+
+/// Importance of a finding, from the most to the least severe
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Critical,
+    Warning,
+    Suggestion,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Critical, Severity::Warning, Severity::Suggestion];
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Critical => write!(f, "critical"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Suggestion => write!(f, "suggestion"),
+        }
+    }
+}
+
+/// A best practice not followed by an object of the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+/// A best-practice check, called on every group and every dataset of the tree. Each returned
+/// message becomes a finding on the checked object.
+pub trait Check {
+    fn name(&self) -> &'static str;
+
+    fn severity(&self) -> Severity;
+
+    fn check_group(&self, _group: &hdf::GroupTree) -> Vec<String> {
+        vec![]
+    }
+
+    fn check_dataset(&self, _dataset: &hdf::DatasetTree, _ds: &hdf5::Dataset) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// The checks run by default, new checks only need to be added here
+pub(crate) fn default_checks() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(MissingDescription),
+        Box::new(TimestampsAscending),
+        Box::new(RateWithTimestamps),
+        Box::new(SuspiciousConversion),
+        Box::new(EmptyDataset),
+        Box::new(UncompressedLargeDataset),
+        Box::new(SessionMetadata),
+    ]
+}
+
+/// Run the checks on the whole tree, the findings are sorted by severity
+pub(crate) fn inspect(tree: &hdf::GroupTree, checks: &[Box<dyn Check>]) -> Vec<Finding> {
+    inspect_with(tree, checks, &job::Progress::default())
+}
+
+/// Inspect the tree, counting the groups checked in `progress`. Once cancelled the remaining
+/// groups are not checked.
+pub(crate) fn inspect_with(
+    tree: &hdf::GroupTree,
+    checks: &[Box<dyn Check>],
+    progress: &job::Progress,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    progress.set_total(tree.n_groups());
+    inspect_group(tree, checks, progress, &mut findings);
+    findings.sort_by_key(|x| x.severity);
+    findings
+}
+
+fn inspect_group(
+    group: &hdf::GroupTree,
+    checks: &[Box<dyn Check>],
+    progress: &job::Progress,
+    findings: &mut Vec<Finding>,
+) {
+    if progress.is_cancelled() {
+        return;
+    }
+    progress.advance(1);
+    let path = group.handler.name();
+    for check in checks {
+        for message in check.check_group(group) {
+            findings.push(finding(check.as_ref(), &path, message));
+        }
+    }
+    for dataset in &group.datasets {
+        let Ok(ds) = group.handler.dataset(hdf::last_component(&dataset.name)) else {
+            continue;
+        };
+        for check in checks {
+            for message in check.check_dataset(dataset, &ds) {
+                findings.push(finding(check.as_ref(), &dataset.name, message));
+            }
+        }
+    }
+    for subgroup in &group.groups {
+        inspect_group(subgroup, checks, progress, findings);
+    }
+}
+
+fn finding(check: &dyn Check, path: &str, message: String) -> Finding {
+    Finding {
+        check: check.name(),
+        severity: check.severity(),
+        path: path.to_string(),
+        message,
+    }
+}
+
+/// Findings as a JSON array, one object per finding
+pub(crate) fn to_json(file: &str, findings: &[Finding]) -> serde_json::Value {
+    findings
+        .iter()
+        .map(|x| {
+            serde_json::json!({
+                "file": file,
+                "check": x.check,
+                "severity": x.severity.to_string(),
+                "path": x.path,
+                "message": x.message,
+            })
+        })
+        .collect()
+}
+
+/// Typed objects should describe their content. The file itself is described by its session
+/// metadata instead.
+struct MissingDescription;
+
+impl MissingDescription {
+    fn check(
+        neurodata_type: &Option<hdf::NeurodataType>,
+        attributes: &[hdf::Attribute],
+    ) -> Vec<String> {
+        match neurodata_type {
+            Some(x) if x.name != "NWBFile" => {}
+            _ => return vec![],
+        }
+        if !attributes.iter().any(|x| x.name == "description") {
+            return vec!["The description is missing".to_string()];
+        }
+        match hdf::text_attribute(attributes, "description").map(str::trim) {
+            Some(x) if !x.is_empty() && !x.eq_ignore_ascii_case("no description") => vec![],
+            _ => vec!["The description is empty or a placeholder".to_string()],
+        }
+    }
+}
+
+impl Check for MissingDescription {
+    fn name(&self) -> &'static str {
+        "missing_description"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_group(&self, group: &hdf::GroupTree) -> Vec<String> {
+        MissingDescription::check(&group.neurodata_type, &group.attributes)
+    }
+
+    fn check_dataset(&self, dataset: &hdf::DatasetTree, _ds: &hdf5::Dataset) -> Vec<String> {
+        MissingDescription::check(&dataset.neurodata_type, &dataset.attributes)
+    }
+}
+
+/// Timestamps are read by blocks to keep the memory bounded on long recordings
+struct TimestampsAscending;
+
+const TIMESTAMPS_BLOCK: usize = 1 << 20;
+
+impl Check for TimestampsAscending {
+    fn name(&self) -> &'static str {
+        "timestamps_ascending"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn check_dataset(&self, dataset: &hdf::DatasetTree, ds: &hdf5::Dataset) -> Vec<String> {
+        if hdf::last_component(&dataset.name) != "timestamps" || ds.ndim() != 1 {
+            return vec![];
+        }
+        let mut previous = f64::NEG_INFINITY;
+        let len = ds.size();
+        for start in (0..len).step_by(TIMESTAMPS_BLOCK) {
+            let end = (start + TIMESTAMPS_BLOCK).min(len);
+            let Ok(block) = ds.read_slice_1d::<f64, _>(start..end) else {
+                return vec![];
+            };
+            for (i, x) in block.iter().enumerate() {
+                if *x < previous {
+                    let index = start + i;
+                    return vec![format!(
+                        "The timestamps are not ascending, {x} at index {index} follows {previous}"
+                    )];
+                }
+                previous = *x;
+            }
+        }
+        vec![]
+    }
+}
+
+/// `starting_time` and its `rate` are redundant with explicit timestamps
+struct RateWithTimestamps;
+
+impl Check for RateWithTimestamps {
+    fn name(&self) -> &'static str {
+        "rate_with_timestamps"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_group(&self, group: &hdf::GroupTree) -> Vec<String> {
+        let has = |name: &str| {
+            group
+                .datasets
+                .iter()
+                .any(|x| hdf::last_component(&x.name) == name)
+        };
+        match has("timestamps") && has("starting_time") {
+            true => vec!["Both timestamps and a starting time with a rate are stored".to_string()],
+            false => vec![],
+        }
+    }
+}
+
+/// A `conversion` of zero or of an extreme magnitude usually comes from a unit mistake
+struct SuspiciousConversion;
+
+impl Check for SuspiciousConversion {
+    fn name(&self) -> &'static str {
+        "suspicious_conversion"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_dataset(&self, dataset: &hdf::DatasetTree, _ds: &hdf5::Dataset) -> Vec<String> {
+        let conversion = dataset
+            .attributes
+            .iter()
+            .find(|x| x.name == "conversion")
+            .and_then(|x| match x.value {
+                hdf::AttributeValue::Float(x) => Some(x),
+                hdf::AttributeValue::Integer(x) => Some(x as f64),
+                hdf::AttributeValue::Unsigned(x) => Some(x as f64),
+                _ => None,
+            });
+        match conversion {
+            Some(x) if !x.is_finite() || x <= 0.0 || !(1e-12..=1e12).contains(&x) => {
+                vec![format!("The conversion factor {x} is suspicious")]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+struct EmptyDataset;
+
+impl Check for EmptyDataset {
+    fn name(&self) -> &'static str {
+        "empty_dataset"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_dataset(&self, _dataset: &hdf::DatasetTree, ds: &hdf5::Dataset) -> Vec<String> {
+        match !ds.is_scalar() && ds.size() == 0 {
+            true => vec!["The dataset is empty".to_string()],
+            false => vec![],
+        }
+    }
+}
+
+/// Datasets above this size in bytes should be compressed
+const COMPRESSION_THRESHOLD: u64 = 20 * 1024 * 1024;
+
+struct UncompressedLargeDataset;
+
+impl Check for UncompressedLargeDataset {
+    fn name(&self) -> &'static str {
+        "uncompressed_large_dataset"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Suggestion
+    }
+
+    fn check_dataset(&self, _dataset: &hdf::DatasetTree, ds: &hdf5::Dataset) -> Vec<String> {
+        let size = ds.storage_size();
+        match size > COMPRESSION_THRESHOLD && ds.filters().is_empty() {
+            true => vec![format!(
+                "The dataset takes {} MiB without compression",
+                size / (1024 * 1024)
+            )],
+            false => vec![],
+        }
+    }
+}
+
+/// The metadata of the session that archives and search tools rely on
+struct SessionMetadata;
+
+const SESSION_METADATA: [&str; 5] = [
+    "experimenter",
+    "institution",
+    "lab",
+    "session_id",
+    "experiment_description",
+];
+
+impl Check for SessionMetadata {
+    fn name(&self) -> &'static str {
+        "session_metadata"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check_group(&self, group: &hdf::GroupTree) -> Vec<String> {
+        if group
+            .neurodata_type
+            .as_ref()
+            .is_none_or(|x| x.name != "NWBFile")
+        {
+            return vec![];
+        }
+        let general = group
+            .groups
+            .iter()
+            .find(|x| hdf::last_component(&x.handler.name()) == "general");
+        let Some(general) = general else {
+            return vec!["The /general group with the session metadata is missing".to_string()];
+        };
+        let mut messages: Vec<String> = SESSION_METADATA
+            .iter()
+            .filter(|name| {
+                !general
+                    .datasets
+                    .iter()
+                    .any(|x| hdf::last_component(&x.name) == **name)
+            })
+            .map(|name| format!("The session metadata /general/{name} is missing"))
+            .collect();
+        if !general
+            .groups
+            .iter()
+            .any(|x| hdf::last_component(&x.handler.name()) == "subject")
+        {
+            messages.push("The subject of the session is not described".to_string());
+        }
+        messages
+    }
+}
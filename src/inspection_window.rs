@@ -0,0 +1,109 @@
+use crate::display_traits::{Show, View};
+use crate::inspect::{Finding, Severity};
+use crate::job;
+use crate::navigation;
+use eframe::egui;
+use eframe::egui::{Color32, RichText};
+
+/// Lists the best-practice findings of a file, filtered by severity
+pub struct InspectionWindow {
+    file: String,
+    loading: Option<job::Job<Vec<Finding>>>,
+    findings: Vec<Finding>,
+    error: Option<String>,
+    shown: Vec<Severity>,
+}
+
+impl InspectionWindow {
+    /// The findings are listed once `loading` is done
+    pub fn new(file: String, loading: job::Job<Vec<Finding>>) -> Self {
+        Self {
+            file,
+            loading: Some(loading),
+            findings: vec![],
+            error: None,
+            shown: Severity::ALL.to_vec(),
+        }
+    }
+
+    fn receive_findings(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(findings) => self.findings = findings,
+            job::Status::Failed(e) => self.error = Some(e),
+            job::Status::Cancelled => {
+                self.error = Some("The inspection was cancelled".to_string());
+            }
+        }
+        self.loading = None;
+    }
+}
+
+impl Show for InspectionWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(format!("Inspection of {}", self.file))
+            .open(open)
+            .default_width(700.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for InspectionWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.receive_findings();
+        if let Some(loading) = &self.loading {
+            loading.ui(ui);
+            return;
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+            return;
+        }
+        if self.findings.is_empty() {
+            ui.label("✔ The file follows the best practices.");
+            return;
+        }
+        ui.horizontal(|ui| {
+            for severity in Severity::ALL {
+                let count = self
+                    .findings
+                    .iter()
+                    .filter(|x| x.severity == severity)
+                    .count();
+                let mut shown = self.shown.contains(&severity);
+                if ui
+                    .checkbox(&mut shown, format!("{severity} ({count})"))
+                    .changed()
+                {
+                    match shown {
+                        true => self.shown.push(severity),
+                        false => self.shown.retain(|x| *x != severity),
+                    }
+                }
+            }
+        });
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new(format!("{}#findings", self.file))
+                .striped(true)
+                .show(ui, |ui| {
+                    for finding in &self.findings {
+                        if !self.shown.contains(&finding.severity) {
+                            continue;
+                        }
+                        let color = match finding.severity {
+                            Severity::Critical => Color32::RED,
+                            Severity::Warning => Color32::GOLD,
+                            Severity::Suggestion => Color32::GRAY,
+                        };
+                        ui.label(RichText::new(finding.severity.to_string()).color(color));
+                        navigation::link_ui(ui, &self.file, &finding.path);
+                        ui.label(&finding.message).on_hover_text(finding.check);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
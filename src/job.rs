@@ -0,0 +1,142 @@
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+use eframe::egui;
+
+/// Progress of a work done by another thread, and the request to cancel it
+#[derive(Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    /// Amount of work to do, zero if unknown
+    total: AtomicUsize,
+    cancel: AtomicBool,
+}
+
+impl Progress {
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn set_done(&self, done: usize) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, amount: usize) {
+        self.done.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// State of a job, the result is only returned once
+pub enum Status<T> {
+    Running,
+    Done(T),
+    Failed(String),
+    Cancelled,
+}
+
+/// Work running on its own thread so that the interface stays responsive. Dropping the job
+/// cancels it, the work is expected to check `Progress::is_cancelled` regularly.
+pub struct Job<T> {
+    pub title: String,
+    progress: Arc<Progress>,
+    result: mpsc::Receiver<Result<T, String>>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn(
+        title: impl Into<String>,
+        work: impl FnOnce(&Progress) -> Result<T, String> + Send + 'static,
+    ) -> Self {
+        let progress = Arc::new(Progress::default());
+        let (sender, result) = mpsc::channel();
+        let shared = progress.clone();
+        std::thread::spawn(move || {
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| work(&shared)))
+                .unwrap_or_else(|panic| Err(panic_message(panic.as_ref())));
+            if !shared.is_cancelled() {
+                let _ = sender.send(result);
+            }
+        });
+        Self {
+            title: title.into(),
+            progress,
+            result,
+        }
+    }
+}
+
+/// Message of a panic of the work, as reported by the default hook
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    let message = match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(x), _) => *x,
+        (_, Some(x)) => x.as_str(),
+        _ => "unknown error",
+    };
+    format!("The work panicked: {message}")
+}
+
+impl<T> Job<T> {
+    pub fn poll(&self) -> Status<T> {
+        match self.result.try_recv() {
+            Ok(Ok(x)) => Status::Done(x),
+            Ok(Err(e)) => Status::Failed(e),
+            Err(mpsc::TryRecvError::Empty) => Status::Running,
+            Err(mpsc::TryRecvError::Disconnected) if self.progress.is_cancelled() => {
+                Status::Cancelled
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Status::Failed("The work stopped without a result".to_string())
+            }
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    /// Progress bar of the job with a button to cancel it
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let (done, total) = (self.progress.done(), self.progress.total());
+            let bar = match total {
+                0 => egui::ProgressBar::new(0.0)
+                    .text(format!("{} ({done})", self.title))
+                    .animate(true),
+                _ => egui::ProgressBar::new(done as f32 / total as f32).text(format!(
+                    "{} ({}%)",
+                    self.title,
+                    100 * done / total
+                )),
+            };
+            ui.add(bar.desired_width(300.0));
+            if ui.button("Cancel").clicked() {
+                self.cancel();
+            }
+        });
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.progress.cancel();
+    }
+}
@@ -0,0 +1,414 @@
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::job;
+
+/// Number of blocks of a level merged into one block of the next level
+const FACTOR: usize = 8;
+
+/// Values kept in memory for the overview of a recording read from the file
+const OVERVIEW_BUDGET: usize = 1 << 24;
+
+/// Values read from the file at once while building an overview
+const READ_BUDGET: usize = 1 << 22;
+
+/// First bytes of an overview saved next to its file
+const MAGIC: &[u8] = b"NWBVLOD1";
+
+/// Minimum and maximum of consecutive blocks of `block` samples
+#[derive(Clone, Debug, PartialEq)]
+struct Level {
+    block: usize,
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+/// Min/max summaries of a signal at decreasing resolutions, each level merging `FACTOR` blocks of
+/// the previous one. Envelopes of any range are then computed in a time proportional to the
+/// number of pixels rather than to the number of samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pyramid {
+    len: usize,
+    levels: Vec<Level>,
+}
+
+impl Pyramid {
+    pub fn new(data: &[f64]) -> Self {
+        let (min, max) = block_extrema(data, FACTOR);
+        Self::from_level(data.len(), FACTOR, min, max)
+    }
+
+    /// Pyramid of `len` samples whose finest level has blocks of `block` samples
+    pub fn from_level(len: usize, block: usize, min: Vec<f64>, max: Vec<f64>) -> Self {
+        let mut levels = vec![Level { block, min, max }];
+        while let Some(level) = levels.last().filter(|x| x.min.len() > FACTOR) {
+            let merged = Level {
+                block: level.block * FACTOR,
+                min: level
+                    .min
+                    .chunks(FACTOR)
+                    .filter_map(|x| x.iter().copied().reduce(f64::min))
+                    .collect(),
+                max: level
+                    .max
+                    .chunks(FACTOR)
+                    .filter_map(|x| x.iter().copied().reduce(f64::max))
+                    .collect(),
+            };
+            levels.push(merged);
+        }
+        Self { len, levels }
+    }
+
+    /// Minimum and maximum of all the samples, `None` without samples
+    pub fn extrema(&self) -> Option<(f64, f64)> {
+        let level = &self.levels[self.levels.len() - 1];
+        let low = level.min.iter().copied().reduce(f64::min)?;
+        let high = level.max.iter().copied().reduce(f64::max)?;
+        Some((low, high))
+    }
+
+    /// Points drawing the samples of `range` with about `buckets` buckets. Each bucket is drawn
+    /// as its minimum and its maximum in the order they occur, at its first and its last sample,
+    /// so that short peaks stay visible at every zoom level. `None` if the buckets are finer than
+    /// the finest level, the samples are then drawn with `raw_envelope`.
+    pub fn envelope(
+        &self,
+        times: &Times,
+        range: Range<usize>,
+        buckets: usize,
+    ) -> Option<Vec<[f64; 2]>> {
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        let per_bucket = (end - start) / buckets.max(1);
+        // The coarsest level whose blocks fit in a bucket
+        let level = self.levels.iter().rev().find(|x| x.block <= per_bucket)?;
+        Some(self.level_envelope(level, times, start..end, per_bucket))
+    }
+
+    /// Points drawing the samples of `range` with one bucket per block of the finest level, for
+    /// views finer than its blocks whose samples are too many to be read
+    pub fn finest_envelope(&self, times: &Times, range: Range<usize>) -> Vec<[f64; 2]> {
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        self.level_envelope(&self.levels[0], times, start..end, 0)
+    }
+
+    /// Points drawing the samples of `range` with buckets of at least `per_bucket` samples,
+    /// aligned on the blocks of `level`
+    fn level_envelope(
+        &self,
+        level: &Level,
+        times: &Times,
+        range: Range<usize>,
+        per_bucket: usize,
+    ) -> Vec<[f64; 2]> {
+        let block = level.block;
+        let per_bucket = per_bucket.div_ceil(block).max(1);
+        let first = range.start / block;
+        let last = range.end.div_ceil(block).min(level.min.len());
+        let mut points = Vec::with_capacity(2 * (last - first.min(last)).div_ceil(per_bucket));
+        for bucket in (first..last).step_by(per_bucket) {
+            let blocks = bucket..(bucket + per_bucket).min(last);
+            let Some([first_value, last_value]) =
+                ordered_extrema(&level.min[blocks.clone()], &level.max[blocks.clone()])
+            else {
+                continue;
+            };
+            let last_sample = (blocks.end * block).min(self.len) - 1;
+            points.push([times.first_of(blocks.start * block), first_value]);
+            points.push([times.last_of(last_sample), last_value]);
+        }
+        points
+    }
+}
+
+/// Points drawing samples held in memory with about `buckets` buckets, sample by sample if they
+/// are few
+pub fn raw_envelope(x: &[f64], y: &[f64], buckets: usize) -> Vec<[f64; 2]> {
+    let n = x.len().min(y.len());
+    let per_bucket = n / buckets.max(1);
+    if per_bucket <= 2 {
+        return (0..n).map(|i| [x[i], y[i]]).collect();
+    }
+    (0..n)
+        .step_by(per_bucket)
+        .filter_map(|start| {
+            let end = (start + per_bucket).min(n);
+            let [first_value, last_value] = ordered_extrema(&y[start..end], &y[start..end])?;
+            Some([[x[start], first_value], [x[end - 1], last_value]])
+        })
+        .flatten()
+        .collect()
+}
+
+/// Minimum and maximum of a bucket in the order they occur, from the minimum and the maximum of
+/// each of its blocks. Consecutive buckets of a rising or a falling signal are then joined without
+/// drawing a sawtooth. `None` for an empty bucket.
+fn ordered_extrema(min: &[f64], max: &[f64]) -> Option<[f64; 2]> {
+    let (low_at, low) = min
+        .iter()
+        .copied()
+        .enumerate()
+        .reduce(|a, b| if b.1 < a.1 { b } else { a })?;
+    let (high_at, high) = max
+        .iter()
+        .copied()
+        .enumerate()
+        .reduce(|a, b| if b.1 > a.1 { b } else { a })?;
+    match high_at < low_at {
+        true => Some([high, low]),
+        false => Some([low, high]),
+    }
+}
+
+/// Minimum and maximum of the values, `None` without values
+fn extrema(data: &[f64]) -> Option<(f64, f64)> {
+    let low = data.iter().copied().reduce(f64::min)?;
+    let high = data.iter().copied().reduce(f64::max)?;
+    Some((low, high))
+}
+
+/// Minimum and maximum of each block, the chunks are never empty
+fn block_extrema(data: &[f64], block: usize) -> (Vec<f64>, Vec<f64>) {
+    data.chunks(block).filter_map(extrema).unzip()
+}
+
+/// Time of the samples of a signal
+#[derive(Clone, Debug, PartialEq)]
+pub enum Times {
+    /// Times held in memory
+    Samples(Vec<f64>),
+    /// Regular sampling, sample indices for a rate of one starting at zero
+    Rate { start: f64, rate: f64 },
+    /// Times of the first and of the last sample of each block of a recording read from the file
+    Blocks {
+        block: usize,
+        first: Vec<f64>,
+        last: Vec<f64>,
+    },
+}
+
+impl Times {
+    /// Time of the sample `i`, the first sample of a block
+    fn first_of(&self, i: usize) -> f64 {
+        match self {
+            Times::Samples(x) => x[i],
+            Times::Rate { start, rate } => start + i as f64 / rate,
+            Times::Blocks { block, first, .. } => first[i / block],
+        }
+    }
+
+    /// Time of the sample `i`, the last sample of a block
+    fn last_of(&self, i: usize) -> f64 {
+        match self {
+            Times::Blocks { block, last, .. } => last[i / block],
+            _ => self.first_of(i),
+        }
+    }
+
+    /// Times of the samples of `range`, `None` if they must be read from the file
+    pub fn raw(&self, range: Range<usize>) -> Option<Vec<f64>> {
+        match self {
+            Times::Samples(x) => Some(x[range].to_vec()),
+            Times::Rate { .. } => Some(range.map(|i| self.first_of(i)).collect()),
+            Times::Blocks { .. } => None,
+        }
+    }
+
+    /// Indices of the samples within `[x_min, x_max]` among `len` ascending samples, widened so
+    /// that lines reach the borders of the plot
+    pub fn visible_range(&self, x_min: f64, x_max: f64, len: usize) -> Range<usize> {
+        match self {
+            Times::Samples(x) => visible_range(&x[..len.min(x.len())], x_min, x_max),
+            Times::Rate { start, rate } => {
+                let index = |t: f64| ((t - start) * rate).clamp(0.0, len as f64) as usize;
+                let first = index(x_min).saturating_sub(1);
+                first..(index(x_max) + 2).min(len).max(first)
+            }
+            Times::Blocks { block, first, last } => {
+                let start = first.partition_point(|t| *t < x_min).saturating_sub(1) * block;
+                let end = ((last.partition_point(|t| *t <= x_max) + 1) * block).min(len);
+                start.min(end)..end
+            }
+        }
+    }
+}
+
+/// Indices of the samples within `[x_min, x_max]` for ascending `x`, widened by one sample on each
+/// side so that lines reach the borders of the plot
+pub fn visible_range(x: &[f64], x_min: f64, x_max: f64) -> Range<usize> {
+    let start = x.partition_point(|t| *t < x_min).saturating_sub(1);
+    let end = (x.partition_point(|t| *t <= x_max) + 1).min(x.len());
+    start..end.max(start)
+}
+
+/// Pyramids of the channels of a recording read from the file, with the times of their blocks
+/// when the recording has timestamps
+pub struct Overview {
+    pub channels: Vec<Pyramid>,
+    pub times: Option<Times>,
+}
+
+/// Size of the finest blocks of an overview, large enough to keep it within the memory budget
+pub fn base_block(n_rows: usize, n_channels: usize) -> usize {
+    let mut block = FACTOR * FACTOR;
+    while 2 * n_rows.div_ceil(block) * n_channels.max(1) > OVERVIEW_BUDGET {
+        block *= FACTOR;
+    }
+    block
+}
+
+/// Summarize a 1D or 2D dataset by slices of rows, `None` if cancelled. The timestamps, if any,
+/// must have one value per row.
+pub fn build_overview(
+    data: &hdf5::Dataset,
+    timestamps: Option<&hdf5::Dataset>,
+    block: usize,
+    progress: &job::Progress,
+) -> hdf5::Result<Option<Overview>> {
+    let shape = data.shape();
+    let n_rows = shape.first().copied().unwrap_or(0);
+    let n_channels = shape.get(1).copied().unwrap_or(1).max(1);
+    let n_blocks = n_rows.div_ceil(block);
+    let rows_per_read = (READ_BUDGET / n_channels).div_ceil(block) * block;
+    let mut min: Vec<Vec<f64>> = (0..n_channels)
+        .map(|_| Vec::with_capacity(n_blocks))
+        .collect();
+    let mut max = min.clone();
+    let (mut first, mut last) = (Vec::new(), Vec::new());
+    progress.set_total(n_rows);
+    for start in (0..n_rows).step_by(rows_per_read) {
+        if progress.is_cancelled() {
+            return Ok(None);
+        }
+        let end = (start + rows_per_read).min(n_rows);
+        let values = read_rows(data, start..end)?;
+        for c in 0..n_channels {
+            let column: Vec<f64> = values.iter().skip(c).step_by(n_channels).copied().collect();
+            let (low, high) = block_extrema(&column, block);
+            min[c].extend(low);
+            max[c].extend(high);
+        }
+        if let Some(timestamps) = timestamps {
+            let times = timestamps.read_slice_1d::<f64, _>(start..end)?.to_vec();
+            for x in times.chunks(block) {
+                first.push(x[0]);
+                last.push(x[x.len() - 1]);
+            }
+        }
+        progress.set_done(end);
+    }
+    let channels = min
+        .into_iter()
+        .zip(max)
+        .map(|(min, max)| Pyramid::from_level(n_rows, block, min, max))
+        .collect();
+    let times = timestamps.map(|_| Times::Blocks { block, first, last });
+    Ok(Some(Overview { channels, times }))
+}
+
+/// Values of the rows of a 1D or 2D dataset, row after row
+pub fn read_rows(data: &hdf5::Dataset, rows: Range<usize>) -> hdf5::Result<Vec<f64>> {
+    match data.ndim() {
+        1 => Ok(data.read_slice_1d::<f64, _>(rows)?.to_vec()),
+        _ => Ok(data
+            .read_slice_2d::<f64, _>((rows, ..))?
+            .iter()
+            .copied()
+            .collect()),
+    }
+}
+
+/// Save the finest level of the overview, `stamp` identifies the version of the source file
+pub fn save_overview(
+    path: &Path,
+    stamp: u64,
+    channels: &[Pyramid],
+    times: &Times,
+) -> std::io::Result<()> {
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let (n_rows, block) = channels
+        .first()
+        .map(|x| (x.len, x.levels[0].block))
+        .unwrap_or((0, 1));
+    let blocks = match times {
+        Times::Blocks { first, last, .. } => Some((first, last)),
+        _ => None,
+    };
+    out.write_all(MAGIC)?;
+    let header = [
+        stamp,
+        n_rows as u64,
+        channels.len() as u64,
+        block as u64,
+        blocks.is_some() as u64,
+    ];
+    for x in header {
+        out.write_all(&x.to_le_bytes())?;
+    }
+    let levels = channels
+        .iter()
+        .flat_map(|x| x.levels[0].min.iter().chain(&x.levels[0].max));
+    let times = blocks
+        .into_iter()
+        .flat_map(|(first, last)| first.iter().chain(last));
+    for x in levels.chain(times) {
+        out.write_all(&x.to_le_bytes())?;
+    }
+    out.flush()
+}
+
+/// Read an overview saved by `save_overview`, `None` if it is missing or does not match the
+/// dataset
+pub fn load_overview(
+    path: &Path,
+    stamp: u64,
+    n_rows: usize,
+    n_channels: usize,
+) -> Option<Overview> {
+    let bytes = std::fs::read(path).ok()?;
+    let words: Vec<[u8; 8]> = bytes
+        .strip_prefix(MAGIC)?
+        .chunks_exact(8)
+        .map(|x| x.try_into().unwrap())
+        .collect();
+    let header: Vec<u64> = words
+        .iter()
+        .take(5)
+        .map(|x| u64::from_le_bytes(*x))
+        .collect();
+    if header.len() < 5
+        || header[0] != stamp
+        || header[1] != n_rows as u64
+        || header[2] != n_channels as u64
+        || header[3] == 0
+    {
+        return None;
+    }
+    let block = header[3] as usize;
+    let has_times = header[4] == 1;
+    let n_blocks = n_rows.div_ceil(block);
+    let values: Vec<f64> = words[5..].iter().map(|x| f64::from_le_bytes(*x)).collect();
+    if values.len() != (2 * n_channels + 2 * has_times as usize) * n_blocks {
+        return None;
+    }
+    let mut series = values.chunks(n_blocks.max(1)).map(<[f64]>::to_vec);
+    let mut next = || series.next().unwrap_or_default();
+    let channels = (0..n_channels)
+        .map(|_| {
+            let min = next();
+            Pyramid::from_level(n_rows, block, min, next())
+        })
+        .collect();
+    let times = match has_times {
+        true => Some(Times::Blocks {
+            block,
+            first: next(),
+            last: next(),
+        }),
+        false => None,
+    };
+    Some(Overview { channels, times })
+}
@@ -1,13 +1,60 @@
+#![deny(unsafe_code)]
+
+mod cli;
 mod display_traits;
+mod dynamic_table;
+mod dynamic_table_window;
 mod gui;
 mod hdf;
+mod inspect;
+mod inspection_window;
+mod job;
+mod lod;
+mod navigation;
 mod plot;
 mod popup;
+mod psth_window;
+mod raster;
+mod spec;
+mod spikes;
 mod table;
+mod type_window;
+mod validate;
+mod validation_window;
+// Elements of types only known at runtime are read through the HDF5 C API
+#[allow(unsafe_code)]
+mod value;
 use gui::NWBView;
 use image::GenericImageView;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse_args(&args) {
+        Err(error) => {
+            eprintln!("{error}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+        Ok(cli::Command::Help) => println!("{}", cli::USAGE),
+        Ok(cli::Command::Tree(options)) => {
+            if !cli::run_tree(&options) {
+                std::process::exit(1);
+            }
+        }
+        Ok(cli::Command::Validate(files)) => {
+            if !cli::run_validate(&files) {
+                std::process::exit(1);
+            }
+        }
+        Ok(cli::Command::Inspect(options)) => {
+            if !cli::run_inspect(&options) {
+                std::process::exit(1);
+            }
+        }
+        Ok(cli::Command::Gui(files)) => run_gui(files),
+    }
+}
+
+fn run_gui(files: Vec<String>) {
     const ICON: &[u8] = include_bytes!("../static/icon.png");
 
     let mut options = eframe::NativeOptions {
@@ -35,7 +82,16 @@ fn main() {
     eframe::run_native(
         "NWB View",
         options,
-        Box::new(|_cc| Box::<NWBView>::default()),
+        Box::new(move |_cc| {
+            let mut view = NWBView::default();
+            for arg in &files {
+                let target = cli::parse_open_target(arg);
+                for file in cli::expand_path(&target.file) {
+                    view.open_file(file, target.object.clone());
+                }
+            }
+            Box::new(view)
+        }),
     )
     .ok();
 }
@@ -0,0 +1,67 @@
+use eframe::egui;
+
+/// Object of a loaded file to reveal in the tree and to open
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    /// Name of the file, as returned by `hdf5::File::filename`
+    pub file: String,
+    pub path: String,
+    /// Row to scroll to when the object is a table
+    pub row: Option<usize>,
+}
+
+fn request_id() -> egui::Id {
+    egui::Id::new("navigation_request")
+}
+
+/// Ask the main view to navigate to an object, e.g. when a reference is clicked in a window
+pub fn request(ctx: &egui::Context, target: Target) {
+    ctx.data_mut(|data| data.insert_temp(request_id(), target));
+}
+
+/// Take the pending navigation request, if any
+pub fn take_request(ctx: &egui::Context) -> Option<Target> {
+    ctx.data_mut(|data| {
+        let target = data.get_temp::<Target>(request_id());
+        data.remove::<Target>(request_id());
+        target
+    })
+}
+
+/// Clickable path of a referenced object, a plain label if the reference is invalid
+pub fn link_ui(ui: &mut egui::Ui, file: &str, path: &str) {
+    if !path.starts_with('/') {
+        ui.label(path);
+    } else if ui.link(path).on_hover_text("Go to the object").clicked() {
+        request(
+            ui.ctx(),
+            Target {
+                file: file.to_string(),
+                path: path.to_string(),
+                row: None,
+            },
+        );
+    }
+}
+
+/// Clickable label of a row of a table, e.g. an electrode selected by a region
+pub fn row_link_ui(ui: &mut egui::Ui, file: &str, table: &str, row: i64, label: &str) {
+    let Ok(row) = usize::try_from(row) else {
+        ui.label(label);
+        return;
+    };
+    if ui
+        .link(label)
+        .on_hover_text(format!("Go to the row {row} of {table}"))
+        .clicked()
+    {
+        request(
+            ui.ctx(),
+            Target {
+                file: file.to_string(),
+                path: table.to_string(),
+                row: Some(row),
+            },
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use std::path::PathBuf;
+
 use crate::display_traits::{Show, View};
+use crate::dynamic_table;
 use crate::hdf;
+use crate::job;
+use crate::lod;
 use eframe::egui;
 
-#[derive(Clone, PartialEq)]
 pub struct PlotWindow {
     show_axes: [bool; 2],
     allow_drag: bool,
@@ -15,12 +19,39 @@ pub struct PlotWindow {
     proportional: bool,
     changed_proportional: bool,
     title: String,
-    x_data: Vec<f64>,
-    y_data: Vec<f64>,
-    min_value: f64,
-    max_value: f64,
-    n_steps: usize,
-    step_size: usize,
+    /// Time of the samples, or their indices
+    times: lod::Times,
+    samples: Samples,
+    /// Reading of the samples, or of the overview of a long recording
+    loading: Option<job::Job<Loaded>>,
+    error: Option<String>,
+    n_samples: usize,
+    /// Number of channels, the columns of the dataset beyond its first dimension
+    n_channels: usize,
+    /// Raw minimum and maximum of each channel, `None` without samples
+    ranges: Vec<Option<(f64, f64)>>,
+    /// Raw minimum and maximum of all the channels, `None` without samples
+    extrema: Option<(f64, f64)>,
+    channel_labels: Vec<String>,
+    visible: Vec<bool>,
+    /// Whether the channels are drawn one above the other rather than overlaid
+    stacked: bool,
+    changed_channels: bool,
+    /// Min/max summaries of each channel, used to draw the visible range at the plot resolution.
+    /// Empty while the overview of a recording read from the file is being built.
+    pyramids: Vec<lod::Pyramid>,
+    /// Whether the whole recording is drawn, until the plot bounds fit it
+    fit_all: bool,
+    /// Horizontal bounds of the plot in the last frame
+    x_bounds: [f64; 2],
+    /// Names of the values of an enum dataset, shown as y-axis labels
+    categories: Vec<(f64, String)>,
+    /// Whether the x values are times in seconds rather than sample indices
+    x_in_seconds: bool,
+    /// Conversion of the raw data into physical units, `None` for enum data
+    scaling: Option<hdf::Scaling>,
+    show_scaled: bool,
+    changed_scaling: bool,
 }
 
 impl Default for PlotWindow {
@@ -37,12 +68,26 @@ impl Default for PlotWindow {
             proportional: false,
             changed_proportional: false,
             title: "".to_string(),
-            x_data: vec![],
-            y_data: vec![],
-            min_value: 0.0,
-            max_value: 0.0,
-            n_steps: 0,
-            step_size: 0,
+            times: INDICES,
+            samples: Samples::Memory(vec![]),
+            loading: None,
+            error: None,
+            n_samples: 0,
+            n_channels: 0,
+            ranges: vec![],
+            extrema: None,
+            channel_labels: vec![],
+            visible: vec![],
+            stacked: true,
+            changed_channels: false,
+            pyramids: vec![],
+            fit_all: true,
+            x_bounds: [0.0, 0.0],
+            categories: vec![],
+            x_in_seconds: false,
+            scaling: None,
+            show_scaled: true,
+            changed_scaling: false,
         }
     }
 }
@@ -59,18 +104,55 @@ impl Show for PlotWindow {
 
 impl View for PlotWindow {
     fn ui(&mut self, ui: &mut egui::Ui) {
+        self.receive_samples();
         ui.separator();
+        if let Some(loading) = &self.loading {
+            loading.ui(ui);
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if let Samples::File(file) = &mut self.samples {
+            let label = "Save the overview next to the file";
+            if ui
+                .checkbox(&mut file.save, label)
+                .on_hover_text(file.sidecar.display().to_string())
+                .changed()
+                && file.save
+            {
+                self.save_overview();
+            }
+        }
 
         // Show some statistics
         ui.label(egui::RichText::new("Statistics:"));
-        ui.label(egui::RichText::new(format!(
-            "min value={:?}",
-            self.min_value
-        )));
-        ui.label(egui::RichText::new(format!(
-            "max value={:?}",
-            self.max_value
-        )));
+        let unit = self.unit().map(|x| format!(" {x}")).unwrap_or_default();
+        match self.value_range() {
+            None => {
+                ui.label("No data");
+            }
+            Some((min_value, max_value)) => {
+                ui.label(egui::RichText::new(format!(
+                    "min value={min_value:?}{unit}"
+                )));
+                ui.label(egui::RichText::new(format!(
+                    "max value={max_value:?}{unit}"
+                )));
+            }
+        }
+        if let Some(scaling) = self.scaling.as_ref().filter(|x| !x.is_identity()) {
+            let label = format!(
+                "Physical units (raw × {} + {})",
+                scaling.conversion, scaling.offset
+            );
+            if ui.checkbox(&mut self.show_scaled, label).changed() {
+                self.changed_scaling = true;
+            }
+        }
+
+        if self.n_channels > 1 {
+            self.channels_ui(ui);
+        }
 
         // Plot the data
         let mut proportional = self.proportional;
@@ -84,62 +166,326 @@ impl View for PlotWindow {
             self.trace_plot(ui).context_menu(|_ui| {});
         });
 
+        match self.x_in_seconds {
+            true => ui.label("Time (s)"),
+            false => ui.label("Sample index"),
+        };
         ui.label("Zoom in zoom out using ctrl+mouse.");
     }
 }
 
 impl PlotWindow {
+    /// Start reading the samples in the background, the plot is drawn once they are read
     pub fn get_data_from_group(&mut self, hdf5_group: &hdf::GroupTree) {
         self.title = hdf5_group.handler.name();
-        self.y_data = hdf5_group
-            .handler
-            .dataset("data")
-            .unwrap()
-            .read_raw()
-            .unwrap();
-        let has_timestamps: bool = hdf5_group
-            .handler
-            .datasets()
-            .unwrap()
-            .iter()
-            .any(|x| x.name().ends_with("timestamps"));
-        self.x_data = match has_timestamps {
-            false => (0..self.y_data.len())
-                .collect::<Vec<usize>>()
-                .iter()
-                .map(|x| *x as f64)
+        let group = hdf5_group.handler.clone();
+        let data = group.dataset("data").unwrap();
+        let shape = data.shape();
+        self.n_samples = shape.first().copied().unwrap_or(1);
+        self.n_channels = shape.iter().skip(1).product::<usize>().max(1);
+        match data.dtype().and_then(|x| x.to_descriptor()) {
+            Ok(hdf5::types::TypeDescriptor::Enum(enum_type)) => {
+                self.categories = enum_type
+                    .members
+                    .iter()
+                    .map(|x| {
+                        let value = hdf::enum_member_value(&enum_type, x) as f64;
+                        (value, x.name.clone())
+                    })
+                    .collect();
+                self.loading = Some(job::Job::spawn("Reading the samples", move |_| {
+                    let channels = hdf::enum_channels(&data).map_err(|e| e.to_string())?;
+                    Ok(read_in_memory(&group, channels))
+                }));
+            }
+            _ if data.size() > OUT_OF_CORE_VALUES && data.ndim() <= 2 => {
+                self.scaling = Some(hdf::Scaling::read(&data));
+                self.read_from_file(&group, data);
+            }
+            _ => {
+                self.scaling = Some(hdf::Scaling::read(&data));
+                self.loading = Some(job::Job::spawn("Reading the samples", move |progress| {
+                    let channels = read_channels(&data, progress).map_err(|e| e.to_string())?;
+                    Ok(read_in_memory(&group, channels))
+                }));
+            }
+        }
+
+        self.channel_labels = match dynamic_table::electrode_labels(&hdf5_group.handler) {
+            Some(x) if x.len() == self.n_channels => x,
+            _ => (0..self.n_channels)
+                .map(|c| format!("channel {c}"))
                 .collect(),
-            true => hdf5_group
-                .handler
-                .dataset("timestamps")
-                .unwrap()
-                .read_raw()
-                .unwrap(),
         };
+        self.visible = (0..self.n_channels)
+            .map(|c| c < MAX_VISIBLE_CHANNELS)
+            .collect();
+    }
+
+    /// Read the samples of a long recording on demand. Its overview is built in the background,
+    /// or read from the file saved next to the recording by a previous session.
+    fn read_from_file(&mut self, group: &hdf5::Group, data: hdf5::Dataset) {
+        let timestamps = group
+            .dataset("timestamps")
+            .ok()
+            .filter(|x| x.ndim() == 1 && x.size() == self.n_samples);
+        let rate = hdf::sample_rate(group);
+        self.x_in_seconds = timestamps.is_some() || rate.is_some();
+        self.times = match rate {
+            Some((start, rate)) => lod::Times::Rate { start, rate },
+            None => INDICES,
+        };
+
+        let sidecar = PathBuf::from(format!(
+            "{}{}.lod",
+            data.filename(),
+            data.name().replace('/', ".")
+        ));
+        let stamp = std::fs::metadata(data.filename())
+            .and_then(|x| x.modified())
+            .ok()
+            .and_then(|x| x.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|x| x.as_secs())
+            .unwrap_or(0);
+        {
+            let (data, timestamps, sidecar) = (data.clone(), timestamps.clone(), sidecar.clone());
+            let (n_rows, n_channels) = (self.n_samples, self.n_channels);
+            let work = move |progress: &job::Progress| {
+                if let Some(x) = lod::load_overview(&sidecar, stamp, n_rows, n_channels) {
+                    return Ok(Loaded::Overview(x));
+                }
+                let block = lod::base_block(n_rows, n_channels);
+                match lod::build_overview(&data, timestamps.as_ref(), block, progress) {
+                    Ok(Some(x)) => Ok(Loaded::Overview(x)),
+                    Ok(None) => Err("Cancelled".to_string()),
+                    Err(e) => Err(e.to_string()),
+                }
+            };
+            self.loading = Some(job::Job::spawn("Building the overview", work));
+        }
+        self.samples = Samples::File(Box::new(FileSamples {
+            data,
+            timestamps,
+            sidecar,
+            stamp,
+            save: false,
+            window: None,
+        }));
+    }
+
+    /// Install the samples once the thread reading them is done
+    fn receive_samples(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(Loaded::Memory {
+                channels,
+                times,
+                pyramids,
+            }) => {
+                self.x_in_seconds = matches!(times, lod::Times::Samples(_));
+                self.times = times;
+                self.n_samples = channels[0].len();
+                self.set_pyramids(pyramids);
+                self.samples = Samples::Memory(channels);
+            }
+            job::Status::Done(Loaded::Overview(overview)) => {
+                if let Some(times) = overview.times {
+                    self.times = times;
+                }
+                self.set_pyramids(overview.channels);
+                if matches!(&self.samples, Samples::File(x) if x.save) {
+                    self.save_overview();
+                }
+            }
+            job::Status::Failed(e) => self.error = Some(e),
+            job::Status::Cancelled => self.error = Some("The reading was cancelled".to_string()),
+        }
+        self.loading = None;
+        self.changed_channels = true;
+    }
 
-        self.min_value = *self
-            .y_data
+    fn save_overview(&mut self) {
+        let Samples::File(file) = &self.samples else {
+            return;
+        };
+        if self.pyramids.is_empty() {
+            return;
+        }
+        self.error = lod::save_overview(&file.sidecar, file.stamp, &self.pyramids, &self.times)
+            .err()
+            .map(|e| format!("Could not save the overview: {e}"));
+    }
+
+    fn set_pyramids(&mut self, pyramids: Vec<lod::Pyramid>) {
+        self.ranges = pyramids.iter().map(|x| x.extrema()).collect();
+        self.extrema = self
+            .ranges
             .iter()
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap();
-        self.max_value = *self
-            .y_data
+            .flatten()
+            .copied()
+            .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)));
+        self.pyramids = pyramids;
+    }
+
+    /// Times and raw values of a channel over `range`, for views finer than its overview
+    fn raw_samples(
+        &mut self,
+        range: std::ops::Range<usize>,
+        channel: usize,
+    ) -> hdf5::Result<(Vec<f64>, Vec<f64>)> {
+        match &mut self.samples {
+            Samples::Memory(channels) => Ok((
+                self.times.raw(range.clone()).unwrap_or_default(),
+                channels[channel][range].to_vec(),
+            )),
+            Samples::File(file) => file.read(range, channel, &self.times),
+        }
+    }
+
+    /// Value shown for a raw value, in physical units unless the raw view is selected
+    fn value(&self, raw: f64) -> f64 {
+        match &self.scaling {
+            Some(scaling) if self.show_scaled => scaling.apply(raw),
+            _ => raw,
+        }
+    }
+
+    /// Unit of the shown values, raw values have none
+    fn unit(&self) -> Option<&str> {
+        match &self.scaling {
+            Some(scaling) if self.show_scaled => scaling.unit.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Minimum and maximum of the shown values, a negative conversion swaps them. `None` without
+    /// samples.
+    fn value_range(&self) -> Option<(f64, f64)> {
+        let (min, max) = self.extrema?;
+        let (a, b) = (self.value(min), self.value(max));
+        Some((a.min(b), a.max(b)))
+    }
+
+    /// Whether several channels are drawn one above the other
+    fn stacked_view(&self) -> bool {
+        self.stacked && self.n_channels > 1
+    }
+
+    /// Visibility toggles of the channels, with their labels
+    fn channels_ui(&mut self, ui: &mut egui::Ui) {
+        let n_visible = self.visible.iter().filter(|x| **x).count();
+        let title = format!("Channels ({n_visible}/{} shown)", self.visible.len());
+        egui::CollapsingHeader::new(title)
+            .id_source("plot_channels")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.stacked, "Stacked traces").changed() {
+                        self.changed_channels = true;
+                    }
+                    if ui.button("All").clicked() {
+                        self.visible.iter_mut().for_each(|x| *x = true);
+                        self.changed_channels = true;
+                    }
+                    if ui.button("None").clicked() {
+                        self.visible.iter_mut().for_each(|x| *x = false);
+                        self.changed_channels = true;
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for (visible, label) in self.visible.iter_mut().zip(&self.channel_labels) {
+                            if ui.checkbox(visible, label).changed() {
+                                self.changed_channels = true;
+                            }
+                        }
+                    });
+            });
+    }
+
+    /// Visible channels with the shift of their values and the position of their label. Stacked
+    /// channels are centered on their range and drawn from top to bottom, `spacing` apart.
+    fn channel_layout(&self) -> Vec<(usize, f64, f64)> {
+        let channels: Vec<usize> = (0..self.n_channels).filter(|c| self.visible[*c]).collect();
+        let spacing = channels
             .iter()
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap();
+            .filter_map(|c| self.ranges[*c])
+            .map(|(min, max)| (self.value(max) - self.value(min)).abs())
+            .fold(0.0, f64::max);
+        let spacing = if spacing > 0.0 { spacing } else { 1.0 };
+        channels
+            .iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let (min, max) = self.ranges[*c].unwrap_or((0.0, 0.0));
+                let position = -(k as f64) * spacing;
+                match self.stacked_view() {
+                    true => (
+                        *c,
+                        position - (self.value(min) + self.value(max)) / 2.0,
+                        position,
+                    ),
+                    false => (*c, 0.0, 0.0),
+                }
+            })
+            .collect()
+    }
 
-        self.n_steps = self.x_data.len() - 1;
-        self.step_size = compute_step_size(self.n_steps);
+    /// Lines of the visible channels between `x_min` and `x_max`, with about `buckets` points
+    fn channel_lines(&mut self, x_min: f64, x_max: f64, buckets: usize) -> Vec<egui::plot::Line> {
+        use egui::plot::{Line, PlotPoints};
+        if self.pyramids.is_empty() {
+            return vec![];
+        }
+        let range = match self.fit_all || !x_min.is_finite() || !x_max.is_finite() || x_min >= x_max
+        {
+            true => 0..self.n_samples,
+            false => self.times.visible_range(x_min, x_max, self.n_samples),
+        };
+        let mut lines = Vec::new();
+        for (c, shift, _) in self.channel_layout() {
+            let points = match self.pyramids[c].envelope(&self.times, range.clone(), buckets) {
+                Some(x) => x,
+                None if matches!(self.samples, Samples::File(_))
+                    && range.len() > RAW_ROWS_PER_BUCKET * buckets.max(1) =>
+                {
+                    self.pyramids[c].finest_envelope(&self.times, range.clone())
+                }
+                None => match self.raw_samples(range.clone(), c) {
+                    Ok((x, y)) => lod::raw_envelope(&x, &y, buckets),
+                    Err(e) => {
+                        self.error = Some(e.to_string());
+                        vec![]
+                    }
+                },
+            };
+            let line = Line::new(
+                points
+                    .into_iter()
+                    .map(|[x, y]| [x, self.value(y) + shift])
+                    .collect::<PlotPoints>(),
+            );
+            lines.push(match self.n_channels > 1 {
+                true => line.name(&self.channel_labels[c]),
+                false => line,
+            });
+        }
+        lines
     }
 
     fn trace_plot(&mut self, ui: &mut egui::Ui) -> egui::Response {
-        use egui::plot::{Line, PlotPoints};
-        let line = Line::new(
-            (0..=self.n_steps)
-                .step_by(self.step_size)
-                .map(|i| [self.x_data[i], self.y_data[i]])
-                .collect::<PlotPoints>(),
-        );
+        use egui::plot::{GridMark, Legend};
+        let channel_marks: Vec<(f64, String)> = match self.stacked_view() {
+            true => self
+                .channel_layout()
+                .into_iter()
+                .map(|(c, _, position)| (position, self.channel_labels[c].clone()))
+                .collect(),
+            false => vec![],
+        };
         let mut plot = egui::plot::Plot::new("trace_plot")
             .show_axes(self.show_axes)
             .allow_drag(self.allow_drag)
@@ -152,21 +498,221 @@ impl PlotWindow {
         if self.proportional {
             plot = plot.data_aspect(1.0);
         }
-        if self.changed_proportional {
+        if self.x_in_seconds {
+            plot = plot
+                .x_axis_formatter(|value, _| with_unit(value, "s"))
+                .label_formatter(|name, point| {
+                    format!("{name}\nt = {:.6} s\ny = {:.6}", point.x, point.y)
+                        .trim_start()
+                        .to_string()
+                });
+        }
+        if !channel_marks.is_empty() {
+            plot = plot.show_x(true).show_y(false);
+        } else if self.n_channels > 1 {
+            plot = plot.legend(Legend::default());
+        }
+        let marks = match channel_marks.is_empty() {
+            true => self.categories.clone(),
+            false => channel_marks,
+        };
+        if !marks.is_empty() {
+            let categories = marks;
+            let values: Vec<f64> = categories.iter().map(|x| x.0).collect();
+            plot = plot
+                .y_grid_spacer(move |_| {
+                    values
+                        .iter()
+                        .map(|x| GridMark {
+                            value: *x,
+                            step_size: 1.0,
+                        })
+                        .collect()
+                })
+                .y_axis_formatter(move |value, _| {
+                    categories
+                        .iter()
+                        .find(|x| (x.0 - value).abs() < 1e-9)
+                        .map(|x| x.1.clone())
+                        .unwrap_or_default()
+                });
+        }
+        if let Some(unit) = self
+            .unit()
+            .map(str::to_string)
+            .filter(|_| !self.stacked_view())
+        {
+            plot = plot.y_axis_formatter(move |value, _| with_unit(value, &unit));
+        }
+        if self.changed_proportional || self.changed_scaling || self.changed_channels {
             self.changed_proportional = false;
+            self.changed_scaling = false;
+            self.changed_channels = false;
+            self.fit_all = true;
             plot = plot.reset();
         }
-        plot.show(ui, |plot_ui| plot_ui.line(line)).response
+        let buckets = self.width as usize;
+        let response = plot
+            .show(ui, |plot_ui| {
+                let bounds = plot_ui.plot_bounds();
+                let x_bounds = [bounds.min()[0], bounds.max()[0]];
+                for line in self.channel_lines(x_bounds[0], x_bounds[1], buckets) {
+                    plot_ui.line(line);
+                }
+                // The lines follow the bounds of the previous frame, draw again once they settle
+                if x_bounds != self.x_bounds {
+                    self.x_bounds = x_bounds;
+                    plot_ui.ctx().request_repaint();
+                }
+            })
+            .response;
+        self.fit_all = false;
+        response
+    }
+}
+
+/// Datasets with more values are read on demand rather than at once
+const OUT_OF_CORE_VALUES: usize = 1 << 24;
+
+/// Rows read from the file per bucket at most when zooming in further than the overview, wider
+/// views are drawn from the finest level of the overview
+const RAW_ROWS_PER_BUCKET: usize = 16;
+
+/// Times of samples without times
+const INDICES: lod::Times = lod::Times::Rate {
+    start: 0.0,
+    rate: 1.0,
+};
+
+/// Where the samples of the channels are read from
+enum Samples {
+    Memory(Vec<Vec<f64>>),
+    File(Box<FileSamples>),
+}
+
+/// A recording too long to be held in memory, only the rows in view are read when zooming in
+/// further than its overview
+struct FileSamples {
+    data: hdf5::Dataset,
+    timestamps: Option<hdf5::Dataset>,
+    /// Where the overview is saved, next to the file
+    sidecar: PathBuf,
+    /// Modification time of the file, an overview saved for another version is rebuilt
+    stamp: u64,
+    save: bool,
+    /// Rows last read from the file, reused while the view stays within them
+    window: Option<RawWindow>,
+}
+
+struct RawWindow {
+    rows: std::ops::Range<usize>,
+    times: Vec<f64>,
+    channels: std::collections::HashMap<usize, Vec<f64>>,
+}
+
+impl FileSamples {
+    /// Times and values of a channel over `rows`, read from the file unless the last read
+    /// covers them. The rows are read with a margin so that panning does not read every frame.
+    fn read(
+        &mut self,
+        rows: std::ops::Range<usize>,
+        channel: usize,
+        times: &lod::Times,
+    ) -> hdf5::Result<(Vec<f64>, Vec<f64>)> {
+        let covered = self
+            .window
+            .as_ref()
+            .is_some_and(|x| x.rows.start <= rows.start && rows.end <= x.rows.end);
+        if !covered {
+            let margin = rows.len() / 2;
+            let n_rows = self.data.shape()[0];
+            let wide = rows.start.saturating_sub(margin)..(rows.end + margin).min(n_rows);
+            let times = match (times.raw(wide.clone()), &self.timestamps) {
+                (Some(x), _) => x,
+                (None, Some(timestamps)) => timestamps.read_slice_1d(wide.clone())?.to_vec(),
+                (None, None) => return Err("The recording has no timestamps".into()),
+            };
+            self.window = Some(RawWindow {
+                rows: wide,
+                times,
+                channels: Default::default(),
+            });
+        }
+        let window = self.window.as_mut().unwrap();
+        if !window.channels.contains_key(&channel) {
+            let values = match self.data.ndim() {
+                1 => self.data.read_slice_1d(window.rows.clone())?,
+                _ => self.data.read_slice_1d((window.rows.clone(), channel))?,
+            };
+            window.channels.insert(channel, values.to_vec());
+        }
+        let offset = rows.start - window.rows.start..rows.end - window.rows.start;
+        Ok((
+            window.times[offset.clone()].to_vec(),
+            window.channels[&channel][offset].to_vec(),
+        ))
     }
 }
 
-/// Compute the step size for the plot
-fn compute_step_size(n: usize) -> usize {
-    let step_size: usize = if n > 10000 {
-        let log_n = (n as f64).log(10.0).ceil().powi(3);
-        log_n as usize
-    } else {
-        1
+/// Samples read in the background
+enum Loaded {
+    Memory {
+        channels: Vec<Vec<f64>>,
+        times: lod::Times,
+        pyramids: Vec<lod::Pyramid>,
+    },
+    Overview(lod::Overview),
+}
+
+/// Values read at once from the file
+const READ_VALUES: usize = 1 << 22;
+
+/// Read the channels of a dataset, by slices of rows for the datasets of one or two dimensions
+fn read_channels(data: &hdf5::Dataset, progress: &job::Progress) -> hdf5::Result<Vec<Vec<f64>>> {
+    let shape = data.shape();
+    let n_channels: usize = shape.iter().skip(1).product::<usize>().max(1);
+    let mut channels: Vec<Vec<f64>> = (0..n_channels).map(|_| Vec::new()).collect();
+    let mut append = |values: Vec<f64>| {
+        for (c, channel) in channels.iter_mut().enumerate() {
+            channel.extend(values.iter().skip(c).step_by(n_channels));
+        }
     };
-    step_size
+    if shape.is_empty() || shape.len() > 2 {
+        append(data.read_raw()?);
+        return Ok(channels);
+    }
+    let n_rows = shape[0];
+    progress.set_total(n_rows);
+    for start in (0..n_rows).step_by((READ_VALUES / n_channels).max(1)) {
+        if progress.is_cancelled() {
+            return Err("Cancelled".into());
+        }
+        let end = (start + READ_VALUES / n_channels).min(n_rows);
+        append(lod::read_rows(data, start..end)?);
+        progress.set_done(end);
+    }
+    Ok(channels)
+}
+
+/// Samples held in memory, with their times and their summaries
+fn read_in_memory(group: &hdf5::Group, mut channels: Vec<Vec<f64>>) -> Loaded {
+    let times = hdf::sample_times(group, channels[0].len());
+    let n_samples = match &times {
+        Some(x) => x.len().min(channels[0].len()),
+        None => channels[0].len(),
+    };
+    channels.iter_mut().for_each(|x| x.truncate(n_samples));
+    Loaded::Memory {
+        pyramids: channels.iter().map(|x| lod::Pyramid::new(x)).collect(),
+        times: times.map(lod::Times::Samples).unwrap_or(INDICES),
+        channels,
+    }
+}
+
+/// Channels shown when the plot is opened, the others are enabled from the channel list
+const MAX_VISIBLE_CHANNELS: usize = 16;
+
+/// Axis label of a value, rounded like the default labels of egui
+fn with_unit(value: f64, unit: &str) -> String {
+    format!("{} {unit}", egui::emath::round_to_decimals(value, 5))
 }
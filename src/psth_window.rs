@@ -0,0 +1,353 @@
+use crate::display_traits::{Show, View};
+use crate::job;
+use crate::spikes::{self, Trials, Units};
+use eframe::egui;
+use egui::plot::{Legend, Line, LinkedAxisGroup, MarkerShape, Plot, Points, VLine};
+
+/// Peri-stimulus time histograms of the units of a Units table, with the spikes of every trial
+/// aligned to one of the events of a TimeIntervals table
+pub struct PsthWindow {
+    title: String,
+    file: Option<hdf5::File>,
+    loading: Option<job::Job<Units>>,
+    units: Option<Units>,
+    error: Option<String>,
+    /// Paths of the tables of trials to choose from
+    tables: Vec<String>,
+    table: usize,
+    loading_trials: Option<job::Job<Trials>>,
+    trials: Option<Trials>,
+    trials_error: Option<String>,
+    /// Event column the spikes are aligned to
+    alignment: usize,
+    /// Category column the trials are split by, if any
+    split: Option<usize>,
+    unit: usize,
+    /// Width of the bins of the histograms, in seconds
+    bin: f64,
+    /// Times around the alignment events that are shown, in seconds
+    window: (f64, f64),
+    linked_axes: LinkedAxisGroup,
+}
+
+impl PsthWindow {
+    /// Window of the units of a group, with the given TimeIntervals tables of its file to choose
+    /// the trials from
+    pub fn new(group: &hdf5::Group, tables: Vec<String>) -> Self {
+        let file = group.file().ok();
+        let units_group = group.clone();
+        let mut window = Self {
+            title: format!("PSTH of {}", group.name()),
+            file,
+            loading: Some(job::Job::spawn(
+                "Reading the spike times",
+                move |progress| Units::read(&units_group, progress),
+            )),
+            units: None,
+            error: None,
+            table: tables
+                .iter()
+                .position(|x| x == "/intervals/trials")
+                .unwrap_or(0),
+            tables,
+            loading_trials: None,
+            trials: None,
+            trials_error: None,
+            alignment: 0,
+            split: None,
+            unit: 0,
+            bin: 0.05,
+            window: (-0.5, 1.0),
+            linked_axes: LinkedAxisGroup::x(),
+        };
+        window.read_trials();
+        window
+    }
+
+    fn receive_units(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(units) => self.units = Some(units),
+            job::Status::Failed(e) => self.error = Some(e),
+            job::Status::Cancelled => self.error = Some("The reading was cancelled".to_string()),
+        }
+        self.loading = None;
+    }
+
+    /// Read the selected table of trials in the background
+    fn read_trials(&mut self) {
+        self.trials = None;
+        self.trials_error = None;
+        self.alignment = 0;
+        self.split = None;
+        let (Some(file), Some(path)) = (&self.file, self.tables.get(self.table)) else {
+            self.loading_trials = None;
+            self.trials_error = Some("The file has no table of trials".to_string());
+            return;
+        };
+        let file = file.clone();
+        let path = path.clone();
+        self.loading_trials = Some(job::Job::spawn("Reading the trials", move |_| {
+            let group = file.group(&path).map_err(|e| e.to_string())?;
+            Trials::read(&group)
+        }));
+    }
+
+    /// Trials read in the background, aligning to the start of the trials by default
+    fn receive_trials(&mut self) {
+        let Some(loading) = &self.loading_trials else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(trials) => {
+                self.alignment = trials
+                    .events
+                    .iter()
+                    .position(|x| x.0 == "start_time")
+                    .unwrap_or(0);
+                self.trials = Some(trials);
+            }
+            job::Status::Failed(e) => self.trials_error = Some(e),
+            job::Status::Cancelled => {
+                self.trials_error = Some("The reading was cancelled".to_string());
+            }
+        }
+        self.loading_trials = None;
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui) {
+        let mut changed_table = false;
+        egui::Grid::new(format!("{}#settings", self.title)).show(ui, |ui| {
+            ui.label("Trials");
+            let selected = self.tables.get(self.table).cloned().unwrap_or_default();
+            egui::ComboBox::from_id_source(format!("{}#table", self.title))
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (i, path) in self.tables.iter().enumerate() {
+                        changed_table |= ui.selectable_value(&mut self.table, i, path).changed();
+                    }
+                });
+            ui.end_row();
+
+            if let Some(trials) = &self.trials {
+                ui.label("Align to");
+                egui::ComboBox::from_id_source(format!("{}#alignment", self.title))
+                    .selected_text(&trials.events[self.alignment].0)
+                    .show_ui(ui, |ui| {
+                        for (i, (name, _)) in trials.events.iter().enumerate() {
+                            ui.selectable_value(&mut self.alignment, i, name);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Split by");
+                let selected = match self.split {
+                    None => "nothing",
+                    Some(i) => &trials.categories[i].0,
+                };
+                egui::ComboBox::from_id_source(format!("{}#split", self.title))
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.split, None, "nothing");
+                        for (i, (name, _)) in trials.categories.iter().enumerate() {
+                            ui.selectable_value(&mut self.split, Some(i), name);
+                        }
+                    });
+                ui.end_row();
+            }
+
+            if let Some(units) = &self.units
+                && !units.ids.is_empty()
+            {
+                ui.label("Unit");
+                ui.horizontal(|ui| {
+                    if ui.small_button("◀").clicked() {
+                        self.unit = self.unit.saturating_sub(1);
+                    }
+                    egui::ComboBox::from_id_source(format!("{}#unit", self.title))
+                        .selected_text(units.ids[self.unit].to_string())
+                        .show_ui(ui, |ui| {
+                            for (i, id) in units.ids.iter().enumerate() {
+                                ui.selectable_value(&mut self.unit, i, id.to_string())
+                                    .on_hover_text(units.describe(i));
+                            }
+                        });
+                    if ui.small_button("▶").clicked() {
+                        self.unit = (self.unit + 1).min(units.ids.len() - 1);
+                    }
+                });
+                ui.end_row();
+            }
+
+            ui.label("Bin size");
+            ui.add(
+                egui::DragValue::new(&mut self.bin)
+                    .speed(0.001)
+                    .clamp_range(0.001..=10.0)
+                    .suffix(" s"),
+            );
+            ui.end_row();
+
+            // The window holds at most the bins drawn by the histogram
+            let longest = spikes::MAX_BINS as f64 * self.bin;
+            ui.label("Window");
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut self.window.0)
+                        .speed(0.01)
+                        .clamp_range(self.window.1 - longest..=self.window.1 - self.bin)
+                        .suffix(" s"),
+                );
+                ui.label("to");
+                ui.add(
+                    egui::DragValue::new(&mut self.window.1)
+                        .speed(0.01)
+                        .clamp_range(self.window.0 + self.bin..=self.window.0 + longest)
+                        .suffix(" s"),
+                );
+            });
+            ui.end_row();
+        });
+        if changed_table {
+            self.read_trials();
+        }
+    }
+
+    /// Histograms and aligned spikes of the selected unit, one color per group of trials
+    fn plots_ui(&mut self, ui: &mut egui::Ui) {
+        let (Some(units), Some(trials)) = (&self.units, &self.trials) else {
+            return;
+        };
+        let Some(times) = units.spike_times.get(self.unit) else {
+            return;
+        };
+        let alignments = &trials.events[self.alignment].1;
+        let aligned = spikes::aligned_spikes(times, alignments, self.window);
+        let groups = trial_groups(trials, alignments, self.split);
+
+        let mut psth_plot = Plot::new(format!("{}#psth", self.title))
+            .height(200.0)
+            .link_axis(self.linked_axes.clone())
+            .x_axis_formatter(|value, _| format!("{value} s"))
+            .y_axis_formatter(|value, _| format!("{value} Hz"))
+            .label_formatter(|name, point| {
+                format!("{name}\nt = {:.3} s\nrate = {:.2} Hz", point.x, point.y)
+                    .trim_start()
+                    .to_string()
+            });
+        if self.split.is_some() {
+            psth_plot = psth_plot.legend(Legend::default());
+        }
+        psth_plot.show(ui, |plot_ui| {
+            for (i, (label, rows)) in groups.iter().enumerate() {
+                let selected: Vec<&Vec<f64>> = rows.iter().map(|x| &aligned[*x]).collect();
+                let rates = spikes::psth(&selected, self.window, self.bin);
+                let mut points = Vec::with_capacity(2 * rates.len());
+                for (bin, rate) in rates.iter().enumerate() {
+                    let start = self.window.0 + bin as f64 * self.bin;
+                    points.push([start, *rate]);
+                    points.push([start + self.bin, *rate]);
+                }
+                plot_ui.line(Line::new(points).color(group_color(i)).name(label));
+            }
+            plot_ui.vline(VLine::new(0.0).color(egui::Color32::GRAY));
+        });
+
+        let mut row_labels = Vec::new();
+        let raster_plot = Plot::new(format!("{}#raster", self.title))
+            .height(250.0)
+            .link_axis(self.linked_axes.clone())
+            .show_y(false)
+            .x_axis_formatter(|value, _| format!("{value} s"));
+        for (label, rows) in &groups {
+            for trial in rows {
+                row_labels.push(format!("trial {trial} {label}").trim_end().to_string());
+            }
+        }
+        let raster_plot = raster_plot.label_formatter(move |_, point| {
+            let row = point.y.round();
+            match row_labels.get(row as usize).filter(|_| row >= 0.0) {
+                Some(label) => format!("{label}\nt = {:.3} s", point.x),
+                None => String::new(),
+            }
+        });
+        raster_plot.show(ui, |plot_ui| {
+            let mut row = 0;
+            for (i, (_, rows)) in groups.iter().enumerate() {
+                let mut points = Vec::new();
+                for trial in rows {
+                    points.extend(aligned[*trial].iter().map(|x| [*x, row as f64]));
+                    row += 1;
+                }
+                plot_ui.points(
+                    Points::new(points)
+                        .shape(MarkerShape::Circle)
+                        .radius(1.5)
+                        .color(group_color(i)),
+                );
+            }
+            plot_ui.vline(VLine::new(0.0).color(egui::Color32::GRAY));
+        });
+    }
+}
+
+/// Trials with an alignment time, grouped by their label in the split column. A single unnamed
+/// group holds all of them without split.
+fn trial_groups(
+    trials: &Trials,
+    alignments: &[f64],
+    split: Option<usize>,
+) -> Vec<(String, Vec<usize>)> {
+    let valid = (0..alignments.len()).filter(|x| alignments[*x].is_finite());
+    let Some(split) = split else {
+        return vec![(String::new(), valid.collect())];
+    };
+    let labels = &trials.categories[split].1;
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for trial in valid {
+        match groups.iter_mut().find(|x| x.0 == labels[trial]) {
+            Some(group) => group.1.push(trial),
+            None => groups.push((labels[trial].clone(), vec![trial])),
+        }
+    }
+    groups
+}
+
+/// Distinct colors for the groups of trials, spread around the hue circle
+fn group_color(i: usize) -> egui::Color32 {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    egui::epaint::Hsva::new(i as f32 * golden_ratio, 0.85, 0.5, 1.0).into()
+}
+
+impl Show for PsthWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(&self.title)
+            .open(open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for PsthWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.receive_units();
+        self.receive_trials();
+        if let Some(loading) = &self.loading {
+            loading.ui(ui);
+        }
+        if let Some(loading) = &self.loading_trials {
+            loading.ui(ui);
+        }
+        for error in [&self.error, &self.trials_error].into_iter().flatten() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        self.settings_ui(ui);
+        ui.separator();
+        self.plots_ui(ui);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::display_traits::{Show, View};
+use crate::job;
+use crate::spikes::{self, Units};
+use eframe::egui;
+use egui::plot::{MarkerShape, Plot, Points};
+
+/// Shows the spikes of the units of a Units table, one row per unit
+pub struct RasterWindow {
+    title: String,
+    loading: Option<job::Job<Units>>,
+    units: Option<Units>,
+    error: Option<String>,
+    /// Column the units are filtered by, and the text their value must contain
+    filter_column: usize,
+    filter: String,
+    /// Units passing the filter, from the bottom row to the top one
+    shown: Vec<usize>,
+    changed_filter: bool,
+    /// Whether all the spikes are drawn, until the plot bounds fit them
+    fit_all: bool,
+    /// Horizontal bounds of the plot in the last frame
+    x_bounds: [f64; 2],
+}
+
+impl RasterWindow {
+    pub fn new(group: &hdf5::Group) -> Self {
+        let group = group.clone();
+        Self {
+            title: format!("Spikes of {}", group.name()),
+            loading: Some(job::Job::spawn(
+                "Reading the spike times",
+                move |progress| Units::read(&group, progress),
+            )),
+            units: None,
+            error: None,
+            filter_column: 0,
+            filter: String::new(),
+            shown: vec![],
+            changed_filter: false,
+            fit_all: true,
+            x_bounds: [0.0, 0.0],
+        }
+    }
+
+    fn receive_units(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(units) => {
+                self.shown = (0..units.ids.len()).collect();
+                self.units = Some(units);
+            }
+            job::Status::Failed(e) => self.error = Some(e),
+            job::Status::Cancelled => self.error = Some("The reading was cancelled".to_string()),
+        }
+        self.loading = None;
+    }
+
+    fn filter_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(units) = &self.units else {
+            return;
+        };
+        if units.columns.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Filter units by");
+            let selected = units.columns[self.filter_column].as_str();
+            let mut changed = false;
+            egui::ComboBox::from_id_source("raster_filter_column")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (i, name) in units.columns.iter().enumerate() {
+                        changed |= ui
+                            .selectable_value(&mut self.filter_column, i, name)
+                            .changed();
+                    }
+                });
+            changed |= ui
+                .add(egui::TextEdit::singleline(&mut self.filter).hint_text("contains"))
+                .changed();
+            if changed {
+                self.shown = units.filter(self.filter_column, &self.filter);
+                self.changed_filter = true;
+            }
+            ui.label(format!("{} of {} units", self.shown.len(), units.ids.len()));
+        });
+    }
+
+    fn raster_plot(&mut self, ui: &mut egui::Ui) {
+        let Some(units) = &self.units else {
+            return;
+        };
+        let ids: Vec<(f64, String)> = self
+            .shown
+            .iter()
+            .enumerate()
+            .map(|(row, unit)| (row as f64, units.ids[*unit].to_string()))
+            .collect();
+        let descriptions: Vec<String> = self.shown.iter().map(|x| units.describe(*x)).collect();
+        let mut plot = Plot::new("raster_plot")
+            .height(400.0)
+            .x_axis_formatter(|value, _| format!("{value} s"))
+            .y_axis_formatter(move |value, _| {
+                ids.iter()
+                    .find(|x| (x.0 - value).abs() < 1e-9)
+                    .map(|x| x.1.clone())
+                    .unwrap_or_default()
+            })
+            .label_formatter(move |_, point| {
+                let row = point.y.round();
+                match descriptions.get(row as usize).filter(|_| row >= 0.0) {
+                    Some(description) => format!("{description}\nt = {:.6} s", point.x),
+                    None => String::new(),
+                }
+            });
+        if self.changed_filter {
+            self.changed_filter = false;
+            self.fit_all = true;
+            plot = plot.reset();
+        }
+        let buckets = ui.available_width().max(1.0) as usize;
+        let full_range = units.time_range().unwrap_or((0.0, 1.0));
+        plot.show(ui, |plot_ui| {
+            let bounds = plot_ui.plot_bounds();
+            let x_bounds = [bounds.min()[0], bounds.max()[0]];
+            let (x_min, x_max) = match self.fit_all
+                || !x_bounds[0].is_finite()
+                || !x_bounds[1].is_finite()
+                || x_bounds[0] >= x_bounds[1]
+            {
+                true => full_range,
+                false => (x_bounds[0], x_bounds[1]),
+            };
+            let mut points = Vec::new();
+            for (row, unit) in self.shown.iter().enumerate() {
+                let times = &units.spike_times[*unit];
+                for x in spikes::visible_spikes(times, x_min, x_max, buckets) {
+                    points.push([x, row as f64]);
+                }
+            }
+            plot_ui.points(Points::new(points).shape(MarkerShape::Circle).radius(1.5));
+            // The spikes follow the bounds of the previous frame, draw again once they settle
+            if x_bounds != self.x_bounds {
+                self.x_bounds = x_bounds;
+                plot_ui.ctx().request_repaint();
+            }
+        });
+        self.fit_all = false;
+    }
+}
+
+impl Show for RasterWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(&self.title)
+            .open(open)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for RasterWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.receive_units();
+        if let Some(loading) = &self.loading {
+            loading.ui(ui);
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        self.filter_ui(ui);
+        self.raster_plot(ui);
+    }
+}
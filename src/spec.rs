@@ -0,0 +1,392 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::hdf;
+
+/// Number of instances of a group or a dataset expected in its parent
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantity {
+    Exactly(u64),
+    /// `?`
+    ZeroOrOne,
+    /// `*`
+    ZeroOrMore,
+    /// `+`
+    OneOrMore,
+}
+
+impl Quantity {
+    fn parse(value: &Value) -> Quantity {
+        match value {
+            Value::String(x) if x == "?" || x == "zero_or_one" => Quantity::ZeroOrOne,
+            Value::String(x) if x == "*" || x == "zero_or_many" => Quantity::ZeroOrMore,
+            Value::String(x) if x == "+" || x == "one_or_many" => Quantity::OneOrMore,
+            Value::Number(x) => Quantity::Exactly(x.as_u64().unwrap_or(1)),
+            _ => Quantity::Exactly(1),
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        matches!(self, Quantity::Exactly(n) if *n > 0) || *self == Quantity::OneOrMore
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Quantity::Exactly(x) => write!(f, "{x}"),
+            Quantity::ZeroOrOne => write!(f, "?"),
+            Quantity::ZeroOrMore => write!(f, "*"),
+            Quantity::OneOrMore => write!(f, "+"),
+        }
+    }
+}
+
+/// Alternative shapes of a dataset or an attribute, `None` marks a dimension of any length
+pub type Shapes = Vec<Vec<Option<u64>>>;
+
+/// Alternative shapes separated by `|`, dimensions of any length are shown as `*`
+pub fn format_shapes(shapes: &Shapes) -> String {
+    if shapes.is_empty() {
+        return "any".to_string();
+    }
+    let shapes: Vec<String> = shapes
+        .iter()
+        .map(|shape| {
+            let dims: Vec<String> = shape
+                .iter()
+                .map(|x| x.map_or("*".to_string(), |x| x.to_string()))
+                .collect();
+            format!("[{}]", dims.join(", "))
+        })
+        .collect();
+    shapes.join(" | ")
+}
+
+/// Expected attribute of a group or a dataset
+#[derive(Clone, Debug)]
+pub struct AttributeSpec {
+    pub name: String,
+    pub doc: String,
+    pub dtype: Option<String>,
+    pub shapes: Shapes,
+    pub required: bool,
+}
+
+/// Expected content of a group or a dataset, either a type definition or a member of one
+#[derive(Clone, Debug, Default)]
+pub struct ObjectSpec {
+    /// Fixed name of the object, unnamed members are matched by their type
+    pub name: Option<String>,
+    /// Type defined or included by the object
+    pub neurodata_type: Option<String>,
+    pub doc: String,
+    pub quantity: Option<Quantity>,
+    pub dtype: Option<String>,
+    pub shapes: Shapes,
+    pub attributes: Vec<AttributeSpec>,
+    pub datasets: Vec<ObjectSpec>,
+    pub groups: Vec<ObjectSpec>,
+}
+
+impl ObjectSpec {
+    fn parse(value: &Value) -> ObjectSpec {
+        ObjectSpec {
+            name: string(value, "name"),
+            neurodata_type: string(value, "neurodata_type_def")
+                .or_else(|| string(value, "neurodata_type_inc")),
+            doc: string(value, "doc").unwrap_or_default(),
+            quantity: value.get("quantity").map(Quantity::parse),
+            dtype: value.get("dtype").map(dtype_name),
+            shapes: shapes(value.get("shape")),
+            attributes: items(value, "attributes")
+                .map(|x| AttributeSpec {
+                    name: string(x, "name").unwrap_or_default(),
+                    doc: string(x, "doc").unwrap_or_default(),
+                    dtype: x.get("dtype").map(dtype_name),
+                    shapes: shapes(x.get("shape")),
+                    required: x.get("required").and_then(Value::as_bool).unwrap_or(true),
+                })
+                .collect(),
+            datasets: items(value, "datasets").map(ObjectSpec::parse).collect(),
+            groups: items(value, "groups").map(ObjectSpec::parse).collect(),
+        }
+    }
+
+    /// Quantity of a member, unnamed members default to any number of instances
+    pub fn quantity(&self) -> Quantity {
+        match (self.quantity, &self.name) {
+            (Some(x), _) => x,
+            (None, Some(_)) => Quantity::Exactly(1),
+            (None, None) => Quantity::ZeroOrMore,
+        }
+    }
+
+    /// Members of `other` replace the members of the same name, the others are appended
+    fn extend(&mut self, other: &ObjectSpec) {
+        fn merge<T: Clone>(into: &mut Vec<T>, from: &[T], key: impl Fn(&T) -> Option<&str>) {
+            for item in from {
+                match into
+                    .iter()
+                    .position(|x| key(x).is_some() && key(x) == key(item))
+                {
+                    Some(i) => into[i] = item.clone(),
+                    None => into.push(item.clone()),
+                }
+            }
+        }
+        self.name = other.name.clone().or(self.name.take());
+        self.neurodata_type = other.neurodata_type.clone();
+        if !other.doc.is_empty() {
+            self.doc = other.doc.clone();
+        }
+        self.quantity = other.quantity.or(self.quantity);
+        self.dtype = other.dtype.clone().or(self.dtype.take());
+        if !other.shapes.is_empty() {
+            self.shapes = other.shapes.clone();
+        }
+        merge(&mut self.attributes, &other.attributes, |x| Some(&x.name));
+        merge(&mut self.datasets, &other.datasets, |x| x.name.as_deref());
+        merge(&mut self.groups, &other.groups, |x| x.name.as_deref());
+    }
+}
+
+/// Definition of a neurodata type, as cached in the `/specifications` group of a file
+#[derive(Clone, Debug)]
+pub struct TypeSpec {
+    pub name: String,
+    pub namespace: String,
+    pub doc: String,
+    /// Type extended by this one, from `neurodata_type_inc`
+    pub parent: Option<String>,
+    pub spec: ObjectSpec,
+}
+
+/// A namespace cached in the file, e.g. `core`, `hdmf-common` or an extension
+#[derive(Clone, Debug)]
+pub struct NamespaceSpec {
+    pub name: String,
+    pub version: String,
+    /// Namespaces whose types are used by this one
+    pub dependencies: Vec<String>,
+}
+
+/// The neurodata types defined by the specifications embedded in a file
+#[derive(Default)]
+pub struct Registry {
+    pub namespaces: Vec<NamespaceSpec>,
+    types: HashMap<(String, String), TypeSpec>,
+}
+
+impl Registry {
+    /// Read the latest cached version of every namespace, files without specifications give
+    /// an empty registry
+    pub(crate) fn read(root: &hdf5::Group) -> Registry {
+        let mut registry = Registry::default();
+        let Ok(specifications) = root.group("specifications") else {
+            return registry;
+        };
+        for namespace_group in specifications.groups().unwrap_or_default() {
+            let namespace = hdf::last_component(&namespace_group.name()).to_string();
+            let mut versions = namespace_group.groups().unwrap_or_default();
+            versions.sort_by_key(|x| version_key(hdf::last_component(&x.name())));
+            let Some(version) = versions.pop() else {
+                continue;
+            };
+            for document in version.datasets().unwrap_or_default() {
+                let Some(value) = read_text(&document).and_then(|x| parse_document(&x)) else {
+                    eprintln!("Could not parse the specification {}", document.name());
+                    continue;
+                };
+                match hdf::last_component(&document.name()) {
+                    "namespace" => registry.add_namespaces(&value),
+                    _ => registry.add_document(&namespace, &value),
+                }
+            }
+        }
+        registry
+    }
+
+    /// Register the namespaces described by a namespace document
+    pub(crate) fn add_namespaces(&mut self, value: &Value) {
+        for namespace in items(value, "namespaces") {
+            self.namespaces.push(NamespaceSpec {
+                name: string(namespace, "name").unwrap_or_default(),
+                version: string(namespace, "version").unwrap_or_default(),
+                dependencies: items(namespace, "schema")
+                    .filter_map(|x| string(x, "namespace"))
+                    .collect(),
+            });
+        }
+    }
+
+    /// Register the types defined in a spec document, including the nested definitions
+    pub(crate) fn add_document(&mut self, namespace: &str, value: &Value) {
+        for key in ["groups", "datasets"] {
+            for item in items(value, key) {
+                if let Some(name) = string(item, "neurodata_type_def") {
+                    self.types.insert(
+                        (namespace.to_string(), name.clone()),
+                        TypeSpec {
+                            name,
+                            namespace: namespace.to_string(),
+                            doc: string(item, "doc").unwrap_or_default(),
+                            parent: string(item, "neurodata_type_inc"),
+                            spec: ObjectSpec::parse(item),
+                        },
+                    );
+                }
+                self.add_document(namespace, item);
+            }
+        }
+    }
+
+    /// Look a type up, in its own namespace if it is known
+    pub fn get(&self, neurodata_type: &hdf::NeurodataType) -> Option<&TypeSpec> {
+        match &neurodata_type.namespace {
+            Some(namespace) => self.find(namespace, &neurodata_type.name),
+            None => self.types.values().find(|x| x.name == neurodata_type.name),
+        }
+    }
+
+    pub fn namespace(&self, name: &str) -> Option<&NamespaceSpec> {
+        self.namespaces.iter().find(|x| x.name == name)
+    }
+
+    /// Look a type up in a namespace, then in the namespaces it depends on
+    pub fn find(&self, namespace: &str, name: &str) -> Option<&TypeSpec> {
+        let mut searched: Vec<&str> = Vec::new();
+        let mut pending = vec![namespace];
+        while let Some(namespace) = pending.pop() {
+            if searched.contains(&namespace) {
+                continue;
+            }
+            searched.push(namespace);
+            if let Some(x) = self.types.get(&(namespace.to_string(), name.to_string())) {
+                return Some(x);
+            }
+            if let Some(x) = self.namespace(namespace) {
+                pending.extend(x.dependencies.iter().map(String::as_str).rev());
+            }
+        }
+        self.types.values().find(|x| x.name == name)
+    }
+
+    /// The type followed by the types it extends, up to the base type
+    pub fn ancestors(&self, neurodata_type: &hdf::NeurodataType) -> Vec<&TypeSpec> {
+        let mut ancestors: Vec<&TypeSpec> = Vec::new();
+        let mut current = self.get(neurodata_type);
+        while let Some(x) = current {
+            if ancestors
+                .iter()
+                .any(|y| y.name == x.name && y.namespace == x.namespace)
+            {
+                break;
+            }
+            ancestors.push(x);
+            current = x.parent.as_deref().and_then(|y| self.find(&x.namespace, y));
+        }
+        ancestors
+    }
+
+    /// Whether the type is `base` or extends it
+    pub fn is_a(&self, neurodata_type: &hdf::NeurodataType, base: &str) -> bool {
+        neurodata_type.name == base
+            || self
+                .ancestors(neurodata_type)
+                .iter()
+                .any(|x| x.name == base)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Full specification of a type, with the members inherited from the types it extends
+    pub fn inherited_spec(&self, neurodata_type: &hdf::NeurodataType) -> Option<ObjectSpec> {
+        let ancestors = self.ancestors(neurodata_type);
+        let mut spec = ObjectSpec::default();
+        for ancestor in ancestors.iter().rev() {
+            spec.extend(&ancestor.spec);
+        }
+        (!ancestors.is_empty()).then_some(spec)
+    }
+}
+
+/// Specifications are cached as JSON by pynwb and MatNWB, some tools store their YAML sources
+/// instead
+pub(crate) fn parse_document(text: &str) -> Option<Value> {
+    serde_json::from_str(text)
+        .ok()
+        .or_else(|| serde_norway::from_str(text).ok())
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// Basic dtypes are kept as is, compound and reference dtypes are summarised
+fn dtype_name(value: &Value) -> String {
+    match value {
+        Value::String(x) => x.clone(),
+        Value::Array(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|x| {
+                    let name = string(x, "name").unwrap_or_default();
+                    let dtype = x.get("dtype").map(dtype_name).unwrap_or_default();
+                    format!("{name}: {dtype}")
+                })
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Object(_) => match string(value, "target_type") {
+            Some(x) => format!("reference to {x}"),
+            None => "reference".to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// A shape is either a list of dimensions or a list of alternative shapes
+fn shapes(value: Option<&Value>) -> Shapes {
+    let Some(Value::Array(dims)) = value else {
+        return vec![];
+    };
+    if dims.iter().all(|x| !x.is_array()) {
+        return vec![dims.iter().map(Value::as_u64).collect()];
+    }
+    dims.iter()
+        .filter_map(Value::as_array)
+        .map(|x| x.iter().map(Value::as_u64).collect())
+        .collect()
+}
+
+/// Read a scalar string dataset, whatever its string type
+fn read_text(ds: &hdf5::Dataset) -> Option<String> {
+    if let Ok(x) = ds.read_scalar::<hdf5::types::VarLenUnicode>() {
+        return Some(x.to_string());
+    }
+    if let Ok(x) = ds.read_scalar::<hdf5::types::VarLenAscii>() {
+        return Some(x.to_string());
+    }
+    hdf::read_strings(ds).ok()?.pop()
+}
+
+/// Numeric parts of a version, so that `2.10.0` sorts after `2.9.0`
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|x: char| !x.is_ascii_digit())
+        .filter_map(|x| x.parse().ok())
+        .collect()
+}
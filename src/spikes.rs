@@ -0,0 +1,231 @@
+use crate::dynamic_table::{self, DynamicTable};
+use crate::hdf;
+use crate::job;
+use crate::spec;
+
+/// Number of units whose spike times are read at once
+const READ_UNITS: usize = 64;
+
+/// Spike trains of the units of a Units table, with the text of their other columns
+pub struct Units {
+    pub ids: Vec<i64>,
+    /// Spike times of each unit, in seconds
+    pub spike_times: Vec<Vec<f64>>,
+    /// Names of the columns describing the units, e.g. `quality` or `electrodes`
+    pub columns: Vec<String>,
+    /// Text of the describing columns for each unit
+    pub metadata: Vec<Vec<String>>,
+}
+
+impl Units {
+    /// Read the spike times and the one-dimensional columns of the table. Ragged columns other
+    /// than regions, e.g. waveforms, are left out of the metadata.
+    pub(crate) fn read(group: &hdf5::Group, progress: &job::Progress) -> Result<Units, String> {
+        let table = DynamicTable::open(group).map_err(|e| e.to_string())?;
+        let n_units = table.n_rows();
+        let Some(spikes) = table.columns.iter().find(|x| x.name == "spike_times") else {
+            return Err(format!(
+                "The table {} has no spike_times column",
+                table.name
+            ));
+        };
+        progress.set_total(n_units);
+        let mut spike_times = Vec::with_capacity(n_units);
+        for start in (0..n_units).step_by(READ_UNITS) {
+            if progress.is_cancelled() {
+                return Err("The reading was cancelled".to_string());
+            }
+            let rows = start..(start + READ_UNITS).min(n_units);
+            progress.set_done(start);
+            spike_times.extend(
+                dynamic_table::read_values::<f64>(spikes, rows).map_err(|e| e.to_string())?,
+            );
+        }
+
+        let mut columns = Vec::new();
+        let mut metadata = vec![Vec::new(); n_units];
+        for column in &table.columns {
+            let cells = match &column.region {
+                Some(region) => dynamic_table::read_values::<i64>(column, 0..n_units).map(|x| {
+                    x.iter()
+                        .map(|rows| {
+                            let labels: Vec<String> =
+                                rows.iter().map(|row| region.label(*row)).collect();
+                            labels.join(", ")
+                        })
+                        .collect()
+                }),
+                None if column.indices.is_empty() && column.data.ndim() == 1 => {
+                    dynamic_table::read_column(column, 0..n_units)
+                }
+                None => continue,
+            };
+            let Ok(cells) = cells else {
+                continue;
+            };
+            columns.push(column.name.clone());
+            for (unit, cell) in metadata.iter_mut().zip(cells) {
+                unit.push(cell);
+            }
+        }
+        Ok(Units {
+            ids: table.read_ids(0..n_units).map_err(|e| e.to_string())?,
+            spike_times,
+            columns,
+            metadata,
+        })
+    }
+
+    /// Id of a unit followed by its metadata, one column per line
+    pub fn describe(&self, unit: usize) -> String {
+        let mut lines = vec![format!("unit {}", self.ids[unit])];
+        for (name, value) in self.columns.iter().zip(&self.metadata[unit]) {
+            lines.push(format!("{name}: {value}"));
+        }
+        lines.join("\n")
+    }
+
+    /// Units whose value in the column contains the pattern, ignoring the case. All the units
+    /// match an empty pattern.
+    pub fn filter(&self, column: usize, pattern: &str) -> Vec<usize> {
+        let pattern = pattern.to_lowercase();
+        (0..self.ids.len())
+            .filter(|x| {
+                pattern.is_empty()
+                    || self.metadata[*x]
+                        .get(column)
+                        .is_some_and(|x| x.to_lowercase().contains(&pattern))
+            })
+            .collect()
+    }
+
+    /// Times of the first and the last spikes of all the units
+    pub fn time_range(&self) -> Option<(f64, f64)> {
+        let first = self.spike_times.iter().filter_map(|x| x.first()).copied();
+        let last = self.spike_times.iter().filter_map(|x| x.last()).copied();
+        Some((first.reduce(f64::min)?, last.reduce(f64::max)?))
+    }
+}
+
+/// Spikes of a sorted train between `x_min` and `x_max`. When there are more than `buckets`
+/// of them, only the first spike of each of `buckets` equal intervals is kept, which looks the
+/// same once drawn.
+pub(crate) fn visible_spikes(times: &[f64], x_min: f64, x_max: f64, buckets: usize) -> Vec<f64> {
+    let start = times.partition_point(|x| *x < x_min);
+    let end = times.partition_point(|x| *x <= x_max);
+    let visible = &times[start..end.max(start)];
+    if visible.len() <= buckets {
+        return visible.to_vec();
+    }
+    let width = (x_max - x_min) / buckets as f64;
+    let mut spikes = Vec::with_capacity(buckets);
+    let mut last_bucket = None;
+    for x in visible {
+        let bucket = ((x - x_min) / width) as usize;
+        if last_bucket != Some(bucket) {
+            spikes.push(*x);
+            last_bucket = Some(bucket);
+        }
+    }
+    spikes
+}
+
+/// Trials of a TimeIntervals table, e.g. `/intervals/trials`
+pub struct Trials {
+    /// Columns of times of each trial, e.g. `start_time` or a stimulus onset
+    pub events: Vec<(String, Vec<f64>)>,
+    /// Columns of labels of each trial to split the trials by, e.g. a condition
+    pub categories: Vec<(String, Vec<String>)>,
+}
+
+impl Trials {
+    /// Floating point columns are taken as events, the other one-dimensional columns as
+    /// categories
+    pub(crate) fn read(group: &hdf5::Group) -> Result<Trials, String> {
+        let table = DynamicTable::open(group).map_err(|e| e.to_string())?;
+        let n_trials = table.n_rows();
+        let mut events = Vec::new();
+        let mut categories = Vec::new();
+        for column in &table.columns {
+            if !column.indices.is_empty() || column.region.is_some() || column.data.ndim() != 1 {
+                continue;
+            }
+            let is_float = matches!(
+                column.data.dtype().and_then(|x| x.to_descriptor()),
+                Ok(hdf5::types::TypeDescriptor::Float(_))
+            );
+            if is_float {
+                if let Ok(times) = column.data.read_slice_1d::<f64, _>(0..n_trials) {
+                    events.push((column.name.clone(), times.to_vec()));
+                }
+            } else if let Ok(labels) = dynamic_table::read_column(column, 0..n_trials) {
+                categories.push((column.name.clone(), labels));
+            }
+        }
+        if events.is_empty() {
+            return Err(format!("The table {} has no column of times", table.name));
+        }
+        Ok(Trials { events, categories })
+    }
+}
+
+/// Paths of the TimeIntervals tables of a file, e.g. `/intervals/trials` or the epochs of a
+/// processing module
+pub(crate) fn interval_tables(tree: &hdf::GroupTree, registry: &spec::Registry) -> Vec<String> {
+    let mut paths = Vec::new();
+    find_interval_tables(tree, registry, &mut paths);
+    paths.sort();
+    paths
+}
+
+fn find_interval_tables(tree: &hdf::GroupTree, registry: &spec::Registry, paths: &mut Vec<String>) {
+    if tree
+        .neurodata_type
+        .as_ref()
+        .is_some_and(|x| registry.is_a(x, "TimeIntervals"))
+    {
+        paths.push(tree.handler.name());
+    }
+    for group in &tree.groups {
+        find_interval_tables(group, registry, paths);
+    }
+}
+
+/// Times of the spikes of a sorted train relative to each alignment time, between
+/// `window.0` and `window.1`
+pub(crate) fn aligned_spikes(
+    times: &[f64],
+    alignments: &[f64],
+    window: (f64, f64),
+) -> Vec<Vec<f64>> {
+    alignments
+        .iter()
+        .map(|t| {
+            let start = times.partition_point(|x| *x < t + window.0);
+            let end = times.partition_point(|x| *x < t + window.1);
+            times[start..end.max(start)].iter().map(|x| x - t).collect()
+        })
+        .collect()
+}
+
+/// Bins of a histogram at most, the bins past them are left out
+pub(crate) const MAX_BINS: usize = 10_000;
+
+/// Mean firing rate over the trials in each bin of the window, in spikes per second
+pub(crate) fn psth(aligned: &[&Vec<f64>], window: (f64, f64), bin: f64) -> Vec<f64> {
+    let n_bins = ((window.1 - window.0) / bin)
+        .ceil()
+        .clamp(0.0, MAX_BINS as f64) as usize;
+    let mut counts = vec![0_usize; n_bins];
+    for x in aligned.iter().flat_map(|x| x.iter()) {
+        let bin = ((x - window.0) / bin) as usize;
+        if let Some(count) = counts.get_mut(bin) {
+            *count += 1;
+        }
+    }
+    let scale = match aligned.len() {
+        0 => 0.0,
+        n => 1.0 / (n as f64 * bin),
+    };
+    counts.into_iter().map(|x| x as f64 * scale).collect()
+}
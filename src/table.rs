@@ -1,41 +1,220 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
 use crate::display_traits::{Show, View};
+use crate::navigation;
 use eframe::egui;
 use egui_extras::{Column, Size, StripBuilder, TableBuilder};
+use hdf5::{Hyperslab, SliceOrIndex};
+
+/// Number of rows read from the file at once
+const CHUNK_ROWS: usize = 256;
+/// Number of chunks kept in memory
+const CACHE_SIZE: usize = 16;
+/// Longer cell texts are truncated, clicking the cell shows the full text
+const MAX_CELL_CHARS: usize = 60;
+
+/// Converts an element into the text of each of its fields
+type CellFormat<T> = Box<dyn Fn(&T) -> Vec<String>>;
+/// Path of an object targeted by a field, and its row for regions of datasets
+type FieldTarget = (String, Option<usize>);
+/// Target of each field of an element, if any
+type FieldTargets<T> = Box<dyn Fn(&T) -> Vec<Option<FieldTarget>>>;
+/// Reads the elements of the given rows of a dataset, in the slice of the given indices along the
+/// axes after the first two
+type Reader<T> = Box<dyn Fn(&hdf5::Dataset, Range<usize>, &[usize]) -> hdf5::Result<Vec<T>>>;
 
 /// Shows off a table with dynamic layout
+///
+/// The rows are read lazily from the dataset when they become visible.
 pub struct TableWindow<T> {
     name: String,
-    data: Option<Vec<T>>,
+    dataset: Option<hdf5::Dataset>,
     scalar: Option<String>,
+    /// Shape of the dataset
+    shape: Vec<usize>,
+    /// Selected index along each axis after the first two
+    slice_indices: Vec<usize>,
+    /// Recently read chunks of rows of the selected slice, the most recently used last
+    cache: VecDeque<(usize, Result<Vec<T>, String>)>,
+    /// Names of the fields shown for each element, empty for plain values
+    fields: Vec<String>,
+    read: Reader<T>,
+    format: CellFormat<T>,
+    /// Title and full text of the clicked truncated cell
+    expanded_cell: Option<(String, String)>,
+    /// File and indices of the fields holding paths of referenced objects
+    links: Option<(String, Vec<usize>)>,
+    /// File, path of a table and index of the field holding numbers of rows of the table
+    row_links: Option<(String, String, usize)>,
+    /// File and objects targeted by the fields of each element, e.g. referenced regions
+    targets: Option<(String, FieldTargets<T>)>,
+    /// Row to scroll to on the next frame
+    scroll_to: Option<usize>,
+    /// Row reached through a region reference, highlighted
+    selected_row: Option<usize>,
 }
 
-impl<T: std::fmt::Display> Default for TableWindow<T> {
+impl<T: hdf5::H5Type + std::fmt::Display> Default for TableWindow<T> {
     fn default() -> Self {
+        Self::with_format(vec![], |x: &T| vec![x.to_string()])
+    }
+}
+
+impl<T: hdf5::H5Type> TableWindow<T> {
+    /// Table of elements split into the given fields by `format`
+    pub fn with_format(fields: Vec<String>, format: impl Fn(&T) -> Vec<String> + 'static) -> Self {
+        Self::with_reader(fields, read_elements, format)
+    }
+}
+
+/// Selection of the rows of a dataset in the slice of the given indices along the axes after the
+/// first two. Scalar datasets have a single element.
+pub fn selection(
+    dataset: &hdf5::Dataset,
+    rows: Range<usize>,
+    slice_indices: &[usize],
+) -> hdf5::Selection {
+    match dataset.ndim() {
+        0 => hdf5::Selection::All,
+        1 => hdf5::Selection::from(rows),
+        _ => {
+            let mut selection: Vec<SliceOrIndex> = vec![rows.into(), (..).into()];
+            selection.extend(slice_indices.iter().map(|x| SliceOrIndex::from(*x)));
+            hdf5::Selection::from(Hyperslab::from(selection))
+        }
+    }
+}
+
+/// Read the elements of the given rows of a dataset as the type of the table
+fn read_elements<T: hdf5::H5Type>(
+    dataset: &hdf5::Dataset,
+    rows: Range<usize>,
+    slice_indices: &[usize],
+) -> hdf5::Result<Vec<T>> {
+    let selection = selection(dataset, rows, slice_indices);
+    match dataset.ndim() {
+        0 => dataset.read_raw::<T>(),
+        1 => dataset
+            .read_slice_1d::<T, _>(selection)
+            .map(|x| x.into_raw_vec()),
+        _ => dataset
+            .read_slice_2d::<T, _>(selection)
+            .map(|x| x.into_raw_vec()),
+    }
+}
+
+impl<T> TableWindow<T> {
+    /// Table of elements read by `read`, e.g. when their type is only known from the file
+    pub fn with_reader(
+        fields: Vec<String>,
+        read: impl Fn(&hdf5::Dataset, Range<usize>, &[usize]) -> hdf5::Result<Vec<T>> + 'static,
+        format: impl Fn(&T) -> Vec<String> + 'static,
+    ) -> Self {
         Self {
             name: "Table".to_string(),
-            data: None,
+            dataset: None,
             scalar: None,
+            shape: vec![],
+            slice_indices: vec![],
+            cache: VecDeque::new(),
+            fields,
+            read: Box::new(read),
+            format: Box::new(format),
+            expanded_cell: None,
+            links: None,
+            row_links: None,
+            targets: None,
+            scroll_to: None,
+            selected_row: None,
         }
     }
-}
 
-impl<T: std::fmt::Display> TableWindow<T> {
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
 
-    pub fn set_data(&mut self, data: Vec<T>) {
-        self.data = Some(data);
+    pub fn set_dataset(&mut self, dataset: hdf5::Dataset) {
+        let shape = dataset.shape();
+        self.slice_indices = vec![0; shape.len().saturating_sub(2)];
+        self.shape = shape;
+        self.cache.clear();
+        self.dataset = Some(dataset);
+    }
+
+    /// Show the given fields as links to the objects of `file` whose paths they hold
+    pub fn set_links(&mut self, file: String, fields: Vec<usize>) {
+        self.links = Some((file, fields));
+    }
+
+    /// Show the given field as links to the rows of `table` whose numbers it holds
+    pub fn set_row_links(&mut self, file: String, table: String, field: usize) {
+        self.row_links = Some((file, table, field));
+    }
+
+    /// Show the fields of each element as links to the objects of `file` they target
+    pub fn set_targets(
+        &mut self,
+        file: String,
+        targets: impl Fn(&T) -> Vec<Option<FieldTarget>> + 'static,
+    ) {
+        self.targets = Some((file, Box::new(targets)));
+    }
+
+    pub fn scroll_to_row(&mut self, row: usize) {
+        self.scroll_to = Some(row);
+        self.selected_row = Some(row);
     }
 
     pub fn set_scalar(&mut self, scalar: String) {
         self.scalar = Some(scalar);
     }
+
+    /// Text of the single element of a scalar dataset, with its fields separated by commas
+    pub fn read_scalar(&self, dataset: &hdf5::Dataset) -> hdf5::Result<String> {
+        let values = (self.read)(dataset, 0..1, &[])?;
+        let value = values.first().ok_or("The scalar dataset is empty")?;
+        Ok((self.format)(value).join(", "))
+    }
+}
+
+/// Text of a cell, truncated if it is too long. Whether the truncated text was clicked.
+pub fn cell_ui(ui: &mut egui::Ui, text: &str) -> bool {
+    if text.chars().count() <= MAX_CELL_CHARS {
+        ui.label(text);
+        return false;
+    }
+    let short: String = text.chars().take(MAX_CELL_CHARS).collect();
+    let label = egui::Label::new(format!("{short}…")).sense(egui::Sense::click());
+    ui.add(label).on_hover_text("Click to expand").clicked()
 }
 
-impl<T: std::fmt::Display> Show for TableWindow<T> {
+/// Full text of a clicked cell with a button to close it
+pub fn expanded_cell_ui(ui: &mut egui::Ui, expanded_cell: &mut Option<(String, String)>) {
+    let Some((title, text)) = expanded_cell.as_ref() else {
+        return;
+    };
+    let mut close = false;
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.strong(title);
+            close = ui.small_button("❌").clicked();
+        });
+        egui::ScrollArea::vertical()
+            .id_source("expanded_cell")
+            .max_height(120.0)
+            .show(ui, |ui| {
+                ui.label(text);
+            });
+    });
+    if close {
+        *expanded_cell = None;
+    }
+}
+
+impl<T> Show for TableWindow<T> {
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
-        if self.data.is_some() {
+        if self.dataset.is_some() {
             egui::Window::new(&self.name)
                 .open(open)
                 .resizable(true)
@@ -60,8 +239,12 @@ impl<T: std::fmt::Display> Show for TableWindow<T> {
     }
 }
 
-impl<T: std::fmt::Display> View for TableWindow<T> {
+impl<T> View for TableWindow<T> {
     fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.shape.len() > 1 {
+            self.slice_ui(ui);
+        }
+        expanded_cell_ui(ui, &mut self.expanded_cell);
         StripBuilder::new(ui)
             .size(Size::remainder().at_least(50.0)) // for the table
             .vertical(|mut strip| {
@@ -74,11 +257,97 @@ impl<T: std::fmt::Display> View for TableWindow<T> {
     }
 }
 
-impl<T: std::fmt::Display> TableWindow<T> {
+impl<T> TableWindow<T> {
+    /// Shape summary and one selector per axis beyond the displayed rows and columns
+    fn slice_ui(&mut self, ui: &mut egui::Ui) {
+        let dims: Vec<String> = self.shape.iter().map(|x| x.to_string()).collect();
+        ui.label(format!("Shape: ({})", dims.join(" × ")));
+        let mut changed = false;
+        for (axis, index) in self.slice_indices.iter_mut().enumerate() {
+            let axis_len = self.shape[axis + 2];
+            if axis_len == 0 {
+                continue;
+            }
+            changed |= ui
+                .add(egui::Slider::new(index, 0..=axis_len - 1).text(format!("axis {}", axis + 2)))
+                .changed();
+        }
+        if changed {
+            self.cache.clear();
+        }
+    }
+
+    fn n_rows(&self) -> usize {
+        self.shape.first().copied().unwrap_or(0)
+    }
+
+    fn n_columns(&self) -> usize {
+        match self.shape.len() {
+            0 | 1 => 1,
+            _ => self.shape[1],
+        }
+    }
+
+    /// Read the rows of a chunk in the selected slice
+    fn read_chunk(&self, chunk: usize) -> Result<Vec<T>, String> {
+        let dataset = self.dataset.as_ref().unwrap();
+        let start = chunk * CHUNK_ROWS;
+        let end = (start + CHUNK_ROWS).min(self.n_rows());
+        (self.read)(dataset, start..end, &self.slice_indices).map_err(|e| e.to_string())
+    }
+
+    fn n_fields(&self) -> usize {
+        self.fields.len().max(1)
+    }
+
+    /// Text and target of the fields of an element of the selected slice, reading its chunk if it
+    /// is not cached
+    fn cell_texts(&mut self, row: usize, column: usize) -> Vec<(String, Option<FieldTarget>)> {
+        let chunk = row / CHUNK_ROWS;
+        match self.cache.iter().position(|x| x.0 == chunk) {
+            Some(position) => {
+                let entry = self.cache.remove(position).unwrap();
+                self.cache.push_back(entry);
+            }
+            None => {
+                if self.cache.len() >= CACHE_SIZE {
+                    self.cache.pop_front();
+                }
+                let values = self.read_chunk(chunk);
+                self.cache.push_back((chunk, values));
+            }
+        }
+        match &self.cache.back().unwrap().1 {
+            Err(e) => {
+                let mut texts = vec![(String::new(), None); self.n_fields()];
+                texts[0].0 = format!("⚠ {e}");
+                texts
+            }
+            Ok(values) => {
+                let value = &values[(row % CHUNK_ROWS) * self.n_columns() + column];
+                let targets = match &self.targets {
+                    Some((_, targets)) => targets(value),
+                    None => vec![],
+                };
+                let mut targets = targets.into_iter();
+                (self.format)(value)
+                    .into_iter()
+                    .map(|x| (x, targets.next().flatten()))
+                    .collect()
+            }
+        }
+    }
+
     fn table_ui(&mut self, ui: &mut egui::Ui) {
         let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let n_columns = self.n_columns();
+        let n_fields = self.n_fields();
 
-        let table = TableBuilder::new(ui)
+        let mut builder = TableBuilder::new(ui);
+        if let Some(row) = self.scroll_to.take() {
+            builder = builder.scroll_to_row(row, Some(egui::Align::Center));
+        }
+        let table = builder
             .striped(true)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
             .column(
@@ -87,37 +356,81 @@ impl<T: std::fmt::Display> TableWindow<T> {
                     .resizable(true)
                     .clip(true),
             )
-            .column(
-                Column::initial(150.0)
-                    .at_least(40.0)
-                    .resizable(true)
-                    .clip(true),
+            .columns(
+                Column::initial(if n_columns * n_fields == 1 {
+                    150.0
+                } else {
+                    100.0
+                })
+                .at_least(40.0)
+                .resizable(true)
+                .clip(true),
+                n_columns * n_fields,
             );
 
+        let mut titles: Vec<String> = Vec::new();
+        for column_index in 0..n_columns {
+            for field_index in 0..n_fields {
+                titles.push(match (self.shape.len() < 2, self.fields.get(field_index)) {
+                    (true, None) => "Values".to_string(),
+                    (false, None) => column_index.to_string(),
+                    (true, Some(field)) => field.to_string(),
+                    (false, Some(field)) => format!("{column_index}.{field}"),
+                });
+            }
+        }
+
         table
             .header(20.0, |mut header| {
                 header.col(|ui| {
                     ui.strong("Index");
                 });
-                header.col(|ui| {
-                    ui.strong("Values");
-                });
+                for title in &titles {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
             })
             .body(|body| {
-                body.rows(
-                    text_height,
-                    self.data.as_ref().unwrap().capacity(),
-                    |row_index, mut row| {
-                        row.col(|ui| {
-                            ui.label(row_index.to_string());
-                        });
-                        row.col(|ui| {
-                            let item = &self.data.as_ref().unwrap()[row_index];
-                            let item_str = format!("{item}");
-                            ui.label(item_str);
-                        });
-                    },
-                );
+                body.rows(text_height, self.n_rows(), |row_index, mut row| {
+                    row.col(|ui| {
+                        match self.selected_row == Some(row_index) {
+                            true => ui.strong(row_index.to_string()),
+                            false => ui.label(row_index.to_string()),
+                        };
+                    });
+                    let mut titles = titles.iter();
+                    for column_index in 0..n_columns {
+                        let texts = self.cell_texts(row_index, column_index);
+                        for (field_index, (text, target)) in texts.into_iter().enumerate() {
+                            let title = titles.next().unwrap();
+                            row.col(|ui| {
+                                if let Some((file, _)) = &self.targets
+                                    && let Some((path, row)) = target
+                                {
+                                    match row {
+                                        Some(row) => navigation::row_link_ui(
+                                            ui, file, &path, row as i64, &text,
+                                        ),
+                                        None => navigation::link_ui(ui, file, &path),
+                                    }
+                                } else if let Some((file, fields)) = &self.links
+                                    && fields.contains(&field_index)
+                                {
+                                    navigation::link_ui(ui, file, &text);
+                                } else if let Some((file, table, field)) = &self.row_links
+                                    && *field == field_index
+                                    && let Ok(row) = text.parse()
+                                {
+                                    navigation::row_link_ui(ui, file, table, row, &text);
+                                } else if cell_ui(ui, &text) {
+                                    let title = format!("Row {row_index}, {title}");
+                                    self.expanded_cell = Some((title, text));
+                                }
+                            });
+                        }
+                    }
+                });
             });
     }
 }
@@ -0,0 +1,104 @@
+use crate::display_traits::{Show, View};
+use crate::hdf;
+use crate::spec;
+use eframe::egui;
+use eframe::egui::RichText;
+
+/// Shows the specification of a neurodata type, with the members inherited from its base types
+pub struct TypeWindow {
+    title: String,
+    doc: String,
+    ancestors: Vec<String>,
+    spec: spec::ObjectSpec,
+}
+
+impl TypeWindow {
+    /// `None` if the type is not described by the registry
+    pub fn new(registry: &spec::Registry, neurodata_type: &hdf::NeurodataType) -> Option<Self> {
+        let ancestors = registry.ancestors(neurodata_type);
+        let spec = registry.inherited_spec(neurodata_type)?;
+        let title = match registry.namespace(&ancestors[0].namespace) {
+            None => neurodata_type.to_string(),
+            Some(x) => format!("{neurodata_type} ({} {})", x.name, x.version),
+        };
+        Some(Self {
+            title,
+            doc: ancestors[0].doc.clone(),
+            ancestors: ancestors
+                .iter()
+                .map(|x| format!("{}.{}", x.namespace, x.name))
+                .collect(),
+            spec,
+        })
+    }
+}
+
+impl Show for TypeWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(&self.title)
+            .open(open)
+            .default_width(500.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for TypeWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(&self.doc);
+        if self.ancestors.len() > 1 {
+            ui.label(format!("Extends: {}", self.ancestors[1..].join(" → ")));
+        }
+        if let Some(dtype) = &self.spec.dtype {
+            ui.label(format!(
+                "Data type: {dtype}, shape: {}",
+                spec::format_shapes(&self.spec.shapes)
+            ));
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if !self.spec.attributes.is_empty() {
+                ui.heading("Attributes");
+                egui::Grid::new(format!("{}#attributes", self.title))
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for attribute in &self.spec.attributes {
+                            ui.monospace(&attribute.name).on_hover_text(&attribute.doc);
+                            ui.label(attribute.dtype.as_deref().unwrap_or("any"));
+                            ui.label(spec::format_shapes(&attribute.shapes));
+                            match attribute.required {
+                                true => ui.label("required"),
+                                false => ui.weak("optional"),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            }
+            members_ui(ui, &self.title, "Datasets", &self.spec.datasets);
+            members_ui(ui, &self.title, "Groups", &self.spec.groups);
+        });
+    }
+}
+
+/// Expected datasets or groups, named members first show their name then their type. The grid is
+/// identified by the title of the window, several types may be shown at once.
+fn members_ui(ui: &mut egui::Ui, window: &str, title: &str, members: &[spec::ObjectSpec]) {
+    if members.is_empty() {
+        return;
+    }
+    ui.heading(title);
+    egui::Grid::new(format!("{window}#{title}"))
+        .striped(true)
+        .show(ui, |ui| {
+            for member in members {
+                let name = match &member.name {
+                    Some(x) => RichText::new(x).monospace(),
+                    None => RichText::new("<any name>").italics(),
+                };
+                ui.label(name).on_hover_text(&member.doc);
+                ui.label(member.neurodata_type.as_deref().unwrap_or(""));
+                ui.label(member.dtype.as_deref().unwrap_or(""));
+                ui.label(spec::format_shapes(&member.shapes));
+                ui.label(format!("quantity: {}", member.quantity()));
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,321 @@
+use hdf5::types::TypeDescriptor;
+
+use crate::hdf;
+use crate::job;
+use crate::spec;
+
+/// A difference between an object of the file and its specification
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+/// Check every typed group and dataset of the tree against the specifications of its type
+pub(crate) fn validate(tree: &hdf::GroupTree, registry: &spec::Registry) -> Vec<Violation> {
+    validate_with(tree, registry, &job::Progress::default())
+}
+
+/// Validate the tree, counting the groups checked in `progress`. Once cancelled the remaining
+/// groups are not checked.
+pub(crate) fn validate_with(
+    tree: &hdf::GroupTree,
+    registry: &spec::Registry,
+    progress: &job::Progress,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if registry.is_empty() {
+        violations.push(Violation {
+            path: "/".to_string(),
+            message: "The file has no cached specification".to_string(),
+        });
+        return violations;
+    }
+    progress.set_total(tree.n_groups());
+    validate_group_tree(tree, registry, progress, &mut violations);
+    violations
+}
+
+fn validate_group_tree(
+    group: &hdf::GroupTree,
+    registry: &spec::Registry,
+    progress: &job::Progress,
+    violations: &mut Vec<Violation>,
+) {
+    if progress.is_cancelled() {
+        return;
+    }
+    progress.advance(1);
+    if let Some(neurodata_type) = &group.neurodata_type {
+        match registry.inherited_spec(neurodata_type) {
+            None => violations.push(unknown_type(&group.handler.name(), neurodata_type)),
+            Some(spec) => validate_group(group, &spec, registry, violations),
+        }
+    }
+    for dataset in &group.datasets {
+        let Some(neurodata_type) = &dataset.neurodata_type else {
+            continue;
+        };
+        match registry.inherited_spec(neurodata_type) {
+            None => violations.push(unknown_type(&dataset.name, neurodata_type)),
+            Some(spec) => validate_dataset(group, dataset, &spec, violations),
+        }
+    }
+    for subgroup in &group.groups {
+        validate_group_tree(subgroup, registry, progress, violations);
+    }
+}
+
+fn unknown_type(path: &str, neurodata_type: &hdf::NeurodataType) -> Violation {
+    Violation {
+        path: path.to_string(),
+        message: format!("The type {neurodata_type} is not defined by the cached specification"),
+    }
+}
+
+/// Check the attributes and the members of a group, typed members are checked on their own
+fn validate_group(
+    group: &hdf::GroupTree,
+    spec: &spec::ObjectSpec,
+    registry: &spec::Registry,
+    violations: &mut Vec<Violation>,
+) {
+    let path = group.handler.name();
+    validate_attributes(&path, &group.attributes, &spec.attributes, violations);
+
+    for member in &spec.datasets {
+        match &member.name {
+            Some(name) => {
+                let dataset = group
+                    .datasets
+                    .iter()
+                    .find(|x| hdf::last_component(&x.name) == name);
+                match dataset {
+                    Some(dataset) if dataset.neurodata_type.is_none() => {
+                        validate_dataset(group, dataset, member, violations)
+                    }
+                    Some(_) => {}
+                    None if has_link(group, name) => {}
+                    None => check_missing(&path, name, member, "dataset", violations),
+                }
+            }
+            None => {
+                let count = group
+                    .datasets
+                    .iter()
+                    .filter(|x| is_instance(&x.neurodata_type, member, registry))
+                    .count();
+                check_count(&path, member, count, violations);
+            }
+        }
+    }
+
+    for member in &spec.groups {
+        match &member.name {
+            Some(name) => {
+                let subgroup = group
+                    .groups
+                    .iter()
+                    .find(|x| hdf::last_component(&x.handler.name()) == name);
+                match subgroup {
+                    Some(subgroup) if subgroup.neurodata_type.is_none() => {
+                        validate_group(subgroup, member, registry, violations)
+                    }
+                    Some(_) => {}
+                    None if has_link(group, name) => {}
+                    None => check_missing(&path, name, member, "group", violations),
+                }
+            }
+            None => {
+                let count = group
+                    .groups
+                    .iter()
+                    .filter(|x| is_instance(&x.neurodata_type, member, registry))
+                    .count();
+                check_count(&path, member, count, violations);
+            }
+        }
+    }
+}
+
+/// Check the attributes, the data type and the shape of a dataset
+fn validate_dataset(
+    group: &hdf::GroupTree,
+    dataset: &hdf::DatasetTree,
+    spec: &spec::ObjectSpec,
+    violations: &mut Vec<Violation>,
+) {
+    let path = &dataset.name;
+    validate_attributes(path, &dataset.attributes, &spec.attributes, violations);
+    let Ok(ds) = group.handler.dataset(hdf::last_component(path)) else {
+        return;
+    };
+    if let Some(dtype) = &spec.dtype
+        && let Ok(descriptor) = ds.dtype().and_then(|x| x.to_descriptor())
+        && !dtype_matches(dtype, &descriptor)
+    {
+        violations.push(Violation {
+            path: path.clone(),
+            message: format!("Expected the data type {dtype}, found {descriptor}"),
+        });
+    }
+    let shape: Vec<u64> = ds.shape().iter().map(|x| *x as u64).collect();
+    if !spec.shapes.is_empty() && !spec.shapes.iter().any(|x| shape_matches(x, &shape)) {
+        violations.push(Violation {
+            path: path.clone(),
+            message: format!(
+                "The shape {shape:?} does not match any of the expected shapes {}",
+                spec::format_shapes(&spec.shapes)
+            ),
+        });
+    }
+}
+
+fn validate_attributes(
+    path: &str,
+    attributes: &[hdf::Attribute],
+    specs: &[spec::AttributeSpec],
+    violations: &mut Vec<Violation>,
+) {
+    for spec in specs {
+        match attributes.iter().find(|x| x.name == spec.name) {
+            None if spec.required => violations.push(Violation {
+                path: path.to_string(),
+                message: format!("The required attribute '{}' is missing", spec.name),
+            }),
+            None => {}
+            Some(attribute) => {
+                if let Some(dtype) = &spec.dtype
+                    && let Some(expects_text) = is_text_dtype(dtype)
+                    && let Some(is_text) = is_text_value(&attribute.value)
+                    && expects_text != is_text
+                {
+                    violations.push(Violation {
+                        path: path.to_string(),
+                        message: format!(
+                            "The attribute '{}' should be of type {dtype}, found {}",
+                            spec.name, attribute.value
+                        ),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Whether the object has the type of the member or a type extending it
+fn is_instance(
+    neurodata_type: &Option<hdf::NeurodataType>,
+    member: &spec::ObjectSpec,
+    registry: &spec::Registry,
+) -> bool {
+    let (Some(neurodata_type), Some(expected)) = (neurodata_type, &member.neurodata_type) else {
+        return false;
+    };
+    registry.is_a(neurodata_type, expected)
+}
+
+fn has_link(group: &hdf::GroupTree, name: &str) -> bool {
+    group
+        .links
+        .iter()
+        .any(|x| hdf::last_component(&x.name) == name)
+}
+
+fn check_missing(
+    path: &str,
+    name: &str,
+    member: &spec::ObjectSpec,
+    kind: &str,
+    violations: &mut Vec<Violation>,
+) {
+    if member.quantity().is_required() {
+        violations.push(Violation {
+            path: path.to_string(),
+            message: format!("The required {kind} '{name}' is missing"),
+        });
+    }
+}
+
+/// Unnamed members are matched by type, their number must agree with the quantity
+fn check_count(
+    path: &str,
+    member: &spec::ObjectSpec,
+    count: usize,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = member.neurodata_type.as_deref().unwrap_or("?");
+    let valid = match member.quantity() {
+        spec::Quantity::Exactly(n) => count as u64 == n,
+        spec::Quantity::ZeroOrOne => count <= 1,
+        spec::Quantity::ZeroOrMore => true,
+        spec::Quantity::OneOrMore => count >= 1,
+    };
+    if !valid {
+        violations.push(Violation {
+            path: path.to_string(),
+            message: format!(
+                "Expected {} object(s) of type {expected}, found {count}",
+                member.quantity()
+            ),
+        });
+    }
+}
+
+/// `None` for values whose kind can not be told, e.g. references and empty arrays
+fn is_text_value(value: &hdf::AttributeValue) -> Option<bool> {
+    match value {
+        hdf::AttributeValue::Text(_) => Some(true),
+        hdf::AttributeValue::Array(values) => values.first().and_then(is_text_value),
+        hdf::AttributeValue::Reference(_) | hdf::AttributeValue::Unsupported(_) => None,
+        _ => Some(false),
+    }
+}
+
+/// `Some(true)` for text dtypes, `Some(false)` for numeric ones, `None` for the others
+fn is_text_dtype(dtype: &str) -> Option<bool> {
+    match dtype {
+        "text" | "utf" | "utf8" | "utf-8" | "ascii" | "bytes" | "isodatetime" | "datetime" => {
+            Some(true)
+        }
+        x if is_numeric_dtype(x) || x == "bool" => Some(false),
+        _ => None,
+    }
+}
+
+fn is_numeric_dtype(dtype: &str) -> bool {
+    dtype == "numeric"
+        || dtype.starts_with("float")
+        || dtype.starts_with("int")
+        || dtype.starts_with("uint")
+        || matches!(dtype, "double" | "short" | "long")
+}
+
+/// Data types unknown to the validator always match
+fn dtype_matches(dtype: &str, descriptor: &TypeDescriptor) -> bool {
+    use TypeDescriptor as TD;
+    match dtype {
+        _ if is_text_dtype(dtype) == Some(true) => matches!(
+            descriptor,
+            TD::VarLenUnicode | TD::VarLenAscii | TD::FixedAscii(_) | TD::FixedUnicode(_)
+        ),
+        "numeric" => matches!(descriptor, TD::Integer(_) | TD::Unsigned(_) | TD::Float(_)),
+        x if x.starts_with("float") || x == "double" => matches!(descriptor, TD::Float(_)),
+        x if x.starts_with("uint") => matches!(descriptor, TD::Unsigned(_)),
+        x if x.starts_with("int") || x == "short" || x == "long" => {
+            matches!(descriptor, TD::Integer(_) | TD::Unsigned(_))
+        }
+        "bool" => matches!(descriptor, TD::Boolean | TD::Enum(_)),
+        x if x.starts_with('{') => matches!(descriptor, TD::Compound(_)),
+        x if x.starts_with("reference") => matches!(descriptor, TD::Reference(_)),
+        _ => true,
+    }
+}
+
+fn shape_matches(expected: &[Option<u64>], shape: &[u64]) -> bool {
+    expected.len() == shape.len()
+        && expected
+            .iter()
+            .zip(shape)
+            .all(|(x, y)| x.is_none_or(|x| x == *y))
+}
@@ -0,0 +1,79 @@
+use crate::display_traits::{Show, View};
+use crate::job;
+use crate::navigation;
+use crate::validate::Violation;
+use eframe::egui;
+
+/// Lists the violations of the specification found in a file
+pub struct ValidationWindow {
+    file: String,
+    loading: Option<job::Job<Vec<Violation>>>,
+    violations: Vec<Violation>,
+    error: Option<String>,
+}
+
+impl ValidationWindow {
+    /// The violations are listed once `loading` is done
+    pub fn new(file: String, loading: job::Job<Vec<Violation>>) -> Self {
+        Self {
+            file,
+            loading: Some(loading),
+            violations: vec![],
+            error: None,
+        }
+    }
+
+    fn receive_violations(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        match loading.poll() {
+            job::Status::Running => return,
+            job::Status::Done(violations) => self.violations = violations,
+            job::Status::Failed(e) => self.error = Some(e),
+            job::Status::Cancelled => {
+                self.error = Some("The validation was cancelled".to_string());
+            }
+        }
+        self.loading = None;
+    }
+}
+
+impl Show for ValidationWindow {
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(format!("Validation of {}", self.file))
+            .open(open)
+            .default_width(600.0)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl View for ValidationWindow {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.receive_violations();
+        if let Some(loading) = &self.loading {
+            loading.ui(ui);
+            return;
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+            return;
+        }
+        if self.violations.is_empty() {
+            ui.label("✔ The file follows its specification.");
+            return;
+        }
+        ui.label(format!("{} violation(s) found", self.violations.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new(format!("{}#violations", self.file))
+                .striped(true)
+                .show(ui, |ui| {
+                    for violation in &self.violations {
+                        navigation::link_ui(ui, &self.file, &violation.path);
+                        ui.label(&violation.message);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
@@ -0,0 +1,369 @@
+use std::ffi::{CStr, c_char, c_void};
+use std::fmt;
+
+use crate::hdf;
+use hdf5::SliceOrIndex;
+use hdf5::types::{FloatSize, Reference, TypeDescriptor};
+use hdf5_sys::h5::herr_t;
+use hdf5_sys::h5a::H5Aread;
+use hdf5_sys::h5d::H5Dread;
+#[cfg(not(hdf5_1_12))]
+use hdf5_sys::h5d::H5Dvlen_reclaim as H5Treclaim;
+use hdf5_sys::h5i::hid_t;
+use hdf5_sys::h5p::H5P_DEFAULT;
+#[cfg(not(hdf5_1_12))]
+use hdf5_sys::h5r::H5R_type_t::H5R_DATASET_REGION as H5R_DATASET_REGION1;
+#[cfg(hdf5_1_12)]
+use hdf5_sys::h5r::H5R_type_t::H5R_DATASET_REGION1;
+use hdf5_sys::h5r::{H5R_type_t, H5Rget_name, H5Rget_region};
+#[cfg(hdf5_1_12)]
+use hdf5_sys::h5t::H5Treclaim;
+use hdf5_sys::h5t::hvl_t;
+
+/// Element of a dataset of any type, decoded from the layout of the type stored in the file
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    Boolean(bool),
+    Text(String),
+    Array(Vec<Value>),
+    /// Fields of a compound element, in the order of the type
+    Compound(Vec<(String, Value)>),
+    /// Path of a referenced object
+    Reference(String),
+    /// Path of the dataset referenced by a region reference, and the selected region
+    Region(String, hdf5::Selection),
+    /// Element of a type that cannot be decoded, with the reason
+    Invalid(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Integer(x) => write!(f, "{x}"),
+            Value::Unsigned(x) => write!(f, "{x}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Boolean(x) => write!(f, "{x}"),
+            Value::Text(x) => write!(f, "{x}"),
+            Value::Array(values) => write!(f, "{}", format_array(values)),
+            Value::Compound(fields) => {
+                let items: Vec<String> = fields
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+            Value::Reference(x) => write!(f, "{x}"),
+            Value::Region(path, selection) => write!(f, "{path} [{selection}]"),
+            Value::Invalid(x) => write!(f, "<{x}>"),
+        }
+    }
+}
+
+impl Value {
+    /// Values of the fields of compound elements, nested compounds are flattened in the order of
+    /// `fields`. Other elements are their own single field.
+    pub fn leaves(&self) -> Vec<&Value> {
+        match self {
+            Value::Compound(fields) => fields.iter().flat_map(|x| x.1.leaves()).collect(),
+            value => vec![value],
+        }
+    }
+
+    /// Path of the referenced object, and the first selected row of a referenced region
+    pub fn target(&self) -> Option<(String, Option<usize>)> {
+        match self {
+            Value::Reference(path) => Some((path.clone(), None)),
+            Value::Region(path, selection) => Some((path.clone(), first_row(selection))),
+            _ => None,
+        }
+    }
+}
+
+/// First row of a selection, along the first axis
+fn first_row(selection: &hdf5::Selection) -> Option<usize> {
+    match selection {
+        hdf5::Selection::All => Some(0),
+        hdf5::Selection::Points(points) => points
+            .rows()
+            .into_iter()
+            .filter_map(|x| x.first().copied())
+            .min(),
+        hdf5::Selection::Hyperslab(hyperslab) => match hyperslab.first()? {
+            SliceOrIndex::Index(x) => Some(*x),
+            SliceOrIndex::SliceTo { start, .. }
+            | SliceOrIndex::SliceCount { start, .. }
+            | SliceOrIndex::Unlimited { start, .. } => Some(*start),
+        },
+    }
+}
+
+/// Text of an array of values, e.g. `[1, 2, 3] (3)`
+pub fn format_array<T: fmt::Display>(values: &[T]) -> String {
+    let items: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+    format!("[{}] ({})", items.join(", "), values.len())
+}
+
+/// Name and type of every field of a type, nested fields are joined with a dot. Types other than
+/// compounds have a single unnamed field.
+pub(crate) fn fields(descriptor: &TypeDescriptor) -> Vec<(String, TypeDescriptor)> {
+    let TypeDescriptor::Compound(compound) = descriptor else {
+        return vec![(String::new(), descriptor.clone())];
+    };
+    let mut fields = Vec::new();
+    for field in &compound.fields {
+        for (name, ty) in self::fields(&field.ty) {
+            match name.is_empty() {
+                true => fields.push((field.name.clone(), ty)),
+                false => fields.push((format!("{}.{name}", field.name), ty)),
+            }
+        }
+    }
+    fields
+}
+
+/// Read the selected elements of a dataset, whatever their type
+pub(crate) fn read(
+    dataset: &hdf5::Dataset,
+    selection: impl Into<hdf5::Selection>,
+) -> hdf5::Result<Vec<Value>> {
+    let file_space = dataset.space()?.select(selection)?;
+    let n = file_space.selection_size();
+    if n == 0 {
+        return Ok(vec![]);
+    }
+    let memory_space = hdf5::Dataspace::try_new(n)?;
+    read_with(dataset, n, |memory_type, buffer| {
+        // SAFETY: the buffer holds the `n` selected elements of the memory type
+        unsafe {
+            H5Dread(
+                dataset.id(),
+                memory_type,
+                memory_space.id(),
+                file_space.id(),
+                H5P_DEFAULT,
+                buffer,
+            )
+        }
+    })
+}
+
+/// Read all the elements of an attribute, whatever their type
+pub(crate) fn read_attribute(attr: &hdf5::Attribute) -> hdf5::Result<Vec<Value>> {
+    // SAFETY: the buffer holds all the elements of the attribute in the memory type
+    read_with(attr, attr.size(), |memory_type, buffer| unsafe {
+        H5Aread(attr.id(), memory_type, buffer)
+    })
+}
+
+/// Elements read in a memory type. The variable-length data allocated by the library for them is
+/// freed when the buffer is dropped, whether they were decoded or the read failed halfway.
+struct Buffer {
+    /// Words keep the elements aligned for the pointers written by the library
+    words: Vec<u64>,
+    memory_type: hdf5::Datatype,
+    memory_space: hdf5::Dataspace,
+}
+
+impl Buffer {
+    fn new(memory_type: hdf5::Datatype, n: usize) -> hdf5::Result<Buffer> {
+        let size = memory_type.size();
+        Ok(Buffer {
+            words: vec![0u64; (n * size).div_ceil(8)],
+            memory_space: hdf5::Dataspace::try_new(n)?,
+            memory_type,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        let len = self.memory_space.size() * self.memory_type.size();
+        // SAFETY: the words are initialized and hold at least `len` bytes
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr().cast::<u8>(), len) }
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        hdf5::sync::sync(|| {
+            // SAFETY: the elements are either zeroed or written by the library in the memory
+            // type, null pointers are skipped
+            unsafe {
+                H5Treclaim(
+                    self.memory_type.id(),
+                    self.memory_space.id(),
+                    H5P_DEFAULT,
+                    self.words.as_mut_ptr().cast(),
+                )
+            };
+        });
+    }
+}
+
+/// Read `n` elements with `read`, which fills a buffer in the given memory type
+///
+/// The elements are read in the C layout of the stored type, then decoded field by field.
+fn read_with(
+    container: &hdf5::Container,
+    n: usize,
+    read: impl FnOnce(hid_t, *mut c_void) -> herr_t,
+) -> hdf5::Result<Vec<Value>> {
+    let descriptor = container.dtype()?.to_descriptor()?.to_c_repr();
+    let mut buffer = Buffer::new(hdf5::Datatype::from_descriptor(&descriptor)?, n)?;
+    hdf5::sync::sync(|| {
+        hdf5::h5check(read(
+            buffer.memory_type.id(),
+            buffer.words.as_mut_ptr().cast(),
+        ))?;
+        Ok(buffer
+            .bytes()
+            .chunks_exact(descriptor.size())
+            .map(|x| decode(&descriptor, x, container))
+            .collect())
+    })
+}
+
+/// Decode an element read in the C layout of `descriptor`
+fn decode(descriptor: &TypeDescriptor, bytes: &[u8], location: &hdf5::Location) -> Value {
+    match descriptor {
+        TypeDescriptor::Integer(size) => Value::Integer(signed(bytes, *size as usize)),
+        TypeDescriptor::Unsigned(size) => Value::Unsigned(unsigned(bytes, *size as usize)),
+        TypeDescriptor::Float(FloatSize::U4) => {
+            Value::Float(f32::from_ne_bytes(array(bytes)).into())
+        }
+        TypeDescriptor::Float(FloatSize::U8) => Value::Float(f64::from_ne_bytes(array(bytes))),
+        TypeDescriptor::Boolean => Value::Boolean(bytes[0] != 0),
+        TypeDescriptor::Enum(enum_type) => {
+            let x = match enum_type.signed {
+                true => signed(bytes, enum_type.size as usize),
+                false => unsigned(bytes, enum_type.size as usize) as i64,
+            };
+            match hdf::enum_member_name(enum_type, x) {
+                Some(name) => Value::Text(name.to_string()),
+                None => Value::Integer(x),
+            }
+        }
+        TypeDescriptor::Compound(compound) => Value::Compound(
+            compound
+                .fields
+                .iter()
+                .map(|x| (x.name.clone(), decode(&x.ty, &bytes[x.offset..], location)))
+                .collect(),
+        ),
+        TypeDescriptor::FixedArray(base, len) => Value::Array(
+            (0..*len)
+                .map(|i| decode(base, &bytes[i * base.size()..], location))
+                .collect(),
+        ),
+        TypeDescriptor::FixedAscii(len) | TypeDescriptor::FixedUnicode(len) => {
+            let bytes = &bytes[..*len];
+            let end = bytes.iter().position(|x| *x == 0).unwrap_or(*len);
+            Value::Text(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+        TypeDescriptor::VarLenAscii | TypeDescriptor::VarLenUnicode => {
+            // SAFETY: the element is a pointer to a NUL-terminated string
+            unsafe {
+                let pointer = bytes.as_ptr().cast::<*const c_char>().read_unaligned();
+                if pointer.is_null() {
+                    return Value::Text(String::new());
+                }
+                Value::Text(CStr::from_ptr(pointer).to_string_lossy().into_owned())
+            }
+        }
+        TypeDescriptor::VarLenArray(base) => {
+            // SAFETY: the element is the length and the pointer of an array
+            let bytes = unsafe {
+                let array = bytes.as_ptr().cast::<hvl_t>().read_unaligned();
+                if array.p.is_null() {
+                    return Value::Array(vec![]);
+                }
+                std::slice::from_raw_parts(array.p.cast::<u8>(), array.len * base.size())
+            };
+            Value::Array(
+                bytes
+                    .chunks_exact(base.size())
+                    .map(|x| decode(base, x, location))
+                    .collect(),
+            )
+        }
+        TypeDescriptor::Reference(Reference::Object) => Value::Reference(reference_name(
+            location,
+            <hdf5::ObjectReference1 as hdf5::ObjectReference>::REF_TYPE,
+            bytes.as_ptr().cast(),
+        )),
+        TypeDescriptor::Reference(Reference::Region) => {
+            let path = reference_name(location, H5R_DATASET_REGION1, bytes.as_ptr().cast());
+            match region_selection(location, bytes.as_ptr().cast()) {
+                Err(e) => Value::Invalid(format!("invalid region of {path}: {e}")),
+                Ok(selection) => Value::Region(path, selection),
+            }
+        }
+        // Half floats and standard references, depending on the features of the library
+        #[allow(unreachable_patterns)]
+        _ => Value::Invalid(format!("{descriptor} is not supported")),
+    }
+}
+
+/// Path of the object targeted by a reference of the given kind
+fn reference_name(location: &hdf5::Location, kind: H5R_type_t, reference: *const c_void) -> String {
+    // SAFETY: `reference` points to a reference of the given kind. Without a buffer the library
+    // only returns the length of the name.
+    let len = unsafe { H5Rget_name(location.id(), kind, reference, std::ptr::null_mut(), 0) };
+    let len = match hdf5::h5check(len) {
+        Err(e) => return format!("<invalid reference: {e}>"),
+        Ok(len) => len as usize,
+    };
+    let mut name = vec![0u8; len + 1];
+    // SAFETY: the buffer fits the name and its NUL terminator
+    let len = unsafe {
+        H5Rget_name(
+            location.id(),
+            kind,
+            reference,
+            name.as_mut_ptr().cast(),
+            name.len(),
+        )
+    };
+    match hdf5::h5check(len) {
+        Err(e) => format!("<invalid reference: {e}>"),
+        Ok(len) => String::from_utf8_lossy(&name[..len as usize]).into_owned(),
+    }
+}
+
+/// Region selected in its dataset by a region reference
+fn region_selection(
+    location: &hdf5::Location,
+    reference: *const c_void,
+) -> hdf5::Result<hdf5::Selection> {
+    // SAFETY: `reference` points to a region reference
+    let space =
+        hdf5::h5check(unsafe { H5Rget_region(location.id(), H5R_DATASET_REGION1, reference) })?;
+    // SAFETY: the library returns a new dataspace, which is closed when dropped
+    let space = unsafe { hdf5::from_id::<hdf5::Dataspace>(space) }?;
+    space.get_selection()
+}
+
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    bytes[..N].try_into().unwrap()
+}
+
+/// Native signed integer of `size` bytes
+fn signed(bytes: &[u8], size: usize) -> i64 {
+    match size {
+        1 => i8::from_ne_bytes(array(bytes)).into(),
+        2 => i16::from_ne_bytes(array(bytes)).into(),
+        4 => i32::from_ne_bytes(array(bytes)).into(),
+        _ => i64::from_ne_bytes(array(bytes)),
+    }
+}
+
+/// Native unsigned integer of `size` bytes
+fn unsigned(bytes: &[u8], size: usize) -> u64 {
+    match size {
+        1 => u8::from_ne_bytes(array(bytes)).into(),
+        2 => u16::from_ne_bytes(array(bytes)).into(),
+        4 => u32::from_ne_bytes(array(bytes)).into(),
+        _ => u64::from_ne_bytes(array(bytes)),
+    }
+}
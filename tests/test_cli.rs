@@ -0,0 +1,147 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/validate.rs"]
+mod validate;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/inspect.rs"]
+mod inspect;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/cli.rs"]
+mod cli;
+
+fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn parse_no_arguments_launches_gui() {
+    assert_eq!(cli::parse_args(&[]), Ok(cli::Command::Gui(vec![])));
+}
+
+#[test]
+fn parse_files_to_open() {
+    assert_eq!(
+        cli::parse_args(&to_args(&["a.nwb", "b.nwb#/acquisition/lick_trace"])),
+        Ok(cli::Command::Gui(to_args(&[
+            "a.nwb",
+            "b.nwb#/acquisition/lick_trace"
+        ])))
+    );
+    assert!(cli::parse_args(&to_args(&["a.nwb", "--json"])).is_err());
+    assert_eq!(
+        cli::parse_open_target("b.nwb#/acquisition/lick_trace"),
+        cli::OpenTarget {
+            file: "b.nwb".to_string(),
+            object: Some("/acquisition/lick_trace".to_string()),
+        }
+    );
+    assert_eq!(cli::parse_open_target("a.nwb").object, None);
+}
+
+#[test]
+fn expand_directories_and_patterns() {
+    let directory = std::env::temp_dir().join("nwbview_test_expand");
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(directory.join("sub")).unwrap();
+    for name in ["a.nwb", "b.nwb", "c.txt", "sub/d.nwb"] {
+        std::fs::write(directory.join(name), b"").unwrap();
+    }
+    let path = |name: &str| directory.join(name).display().to_string();
+
+    assert_eq!(
+        cli::expand_path(&path("")),
+        vec![path("a.nwb"), path("b.nwb"), path("sub/d.nwb")]
+    );
+    assert_eq!(
+        cli::expand_path(&path("*.nwb")),
+        vec![path("a.nwb"), path("b.nwb")]
+    );
+    assert_eq!(cli::expand_path(&path("s?b/*")), vec![path("sub/d.nwb")]);
+    assert_eq!(cli::expand_path("missing.nwb"), vec!["missing.nwb"]);
+    assert!(cli::wildcard_match("a*b*c", "aXbYbZc"));
+    assert!(!cli::wildcard_match("a*b", "aXbY"));
+}
+
+#[test]
+fn parse_tree_arguments() {
+    let command = cli::parse_args(&to_args(&[
+        "tree",
+        "--depth",
+        "2",
+        "-f",
+        "acquisition",
+        "a.nwb",
+        "b.nwb",
+    ]));
+    let expected = cli::TreeOptions {
+        files: vec!["a.nwb".to_string(), "b.nwb".to_string()],
+        max_depth: Some(2),
+        filter: Some("acquisition".to_string()),
+    };
+    assert_eq!(command, Ok(cli::Command::Tree(expected)));
+}
+
+#[test]
+fn parse_tree_without_file() {
+    assert!(cli::parse_args(&to_args(&["tree", "--depth", "1"])).is_err());
+}
+
+#[test]
+fn parse_validate_arguments() {
+    assert_eq!(
+        cli::parse_args(&to_args(&["validate", "a.nwb"])),
+        Ok(cli::Command::Validate(vec!["a.nwb".to_string()]))
+    );
+    assert!(cli::parse_args(&to_args(&["validate"])).is_err());
+}
+
+#[test]
+fn parse_inspect_arguments() {
+    assert_eq!(
+        cli::parse_args(&to_args(&["inspect", "--json", "a.nwb"])),
+        Ok(cli::Command::Inspect(cli::InspectOptions {
+            files: vec!["a.nwb".to_string()],
+            json: true,
+        }))
+    );
+    assert!(cli::parse_args(&to_args(&["inspect", "--json"])).is_err());
+}
+
+#[test]
+fn parse_invalid_depth() {
+    assert!(cli::parse_args(&to_args(&["tree", "--depth", "x", "a.nwb"])).is_err());
+}
+
+#[test]
+fn format_tree_with_depth_limit() {
+    let input_file =
+        hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb").unwrap();
+    let output = cli::format_tree(&input_file.tree, Some(1), None);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines.contains(&"acquisition/"));
+    assert!(lines.iter().all(|x| !x.starts_with(' ')));
+}
@@ -0,0 +1,232 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/dynamic_table.rs"]
+mod dynamic_table;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+fn text(values: &[&str]) -> Vec<hdf5::types::VarLenUnicode> {
+    values.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+fn write<T: hdf5::H5Type>(group: &hdf5::Group, name: &str, values: &[T]) {
+    group
+        .new_dataset_builder()
+        .with_data(values)
+        .create(name)
+        .unwrap();
+}
+
+/// Trials table whose `colnames` order differs from the alphabetical order of its datasets
+fn write_trials(file: &hdf5::File) -> hdf5::Group {
+    let table = file.create_group("trials").unwrap();
+    table
+        .new_attr_builder()
+        .with_data(&text(&["start_time", "stop_time", "condition"]))
+        .create("colnames")
+        .unwrap();
+    table
+        .new_attr::<hdf5::types::VarLenUnicode>()
+        .create("neurodata_type")
+        .unwrap()
+        .write_scalar(
+            &"TimeIntervals"
+                .parse::<hdf5::types::VarLenUnicode>()
+                .unwrap(),
+        )
+        .unwrap();
+    write(&table, "id", &[3_i64, 4, 5]);
+    write(&table, "start_time", &[0.0_f64, 1.5, 3.0]);
+    write(&table, "stop_time", &[1.0_f64, 2.5, 4.0]);
+    write(&table, "condition", &text(&["left", "right", "left"]));
+    table
+}
+
+#[test]
+fn columns_follow_colnames() {
+    let path = std::env::temp_dir().join("nwbview_dynamic_table.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        write_trials(&file);
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let table = dynamic_table::DynamicTable::open(&file.group("trials").unwrap()).unwrap();
+    let names: Vec<&str> = table.columns.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, ["start_time", "stop_time", "condition"]);
+    assert_eq!(table.n_rows(), 3);
+    assert_eq!(table.read_ids(1..3).unwrap(), [4, 5]);
+    assert_eq!(
+        dynamic_table::read_cells(&table.columns[1].data, 0..2).unwrap(),
+        ["1", "2.5"]
+    );
+    assert_eq!(
+        dynamic_table::read_cells(&table.columns[2].data, 1..3).unwrap(),
+        ["right", "left"]
+    );
+}
+
+#[test]
+fn detect_tables_without_specification() {
+    let path = std::env::temp_dir().join("nwbview_dynamic_table_detect.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        write_trials(&file);
+    }
+    let file_tree = hdf::read_nwb_file(path.to_str().unwrap()).unwrap();
+    let registry = spec::Registry::default();
+    assert!(dynamic_table::is_dynamic_table(
+        &file_tree.tree.groups[0],
+        &registry
+    ));
+    assert!(!dynamic_table::is_dynamic_table(&file_tree.tree, &registry));
+}
+
+#[test]
+fn split_ragged_columns() {
+    let path = std::env::temp_dir().join("nwbview_dynamic_table_ragged.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let table = file.create_group("units").unwrap();
+        table
+            .new_attr_builder()
+            .with_data(&text(&["spike_times", "bursts"]))
+            .create("colnames")
+            .unwrap();
+        write(&table, "id", &[0_i64, 1, 2]);
+        write(&table, "spike_times", &[0.1_f64, 0.2, 0.3, 0.4, 0.5]);
+        write(&table, "spike_times_index", &[2_u64, 2, 5]);
+        write(&table, "bursts", &[1_i64, 2, 3, 4, 5, 6]);
+        write(&table, "bursts_index", &[1_u64, 3, 4, 6]);
+        write(&table, "bursts_index_index", &[2_u64, 3, 4]);
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let table = dynamic_table::DynamicTable::open(&file.group("units").unwrap()).unwrap();
+    assert_eq!(table.columns[0].indices.len(), 1);
+    assert_eq!(table.columns[1].indices.len(), 2);
+    assert_eq!(
+        dynamic_table::read_column(&table.columns[0], 0..3).unwrap(),
+        ["[0.1, 0.2] (2)", "[] (0)", "[0.3, 0.4, 0.5] (3)"]
+    );
+    assert_eq!(
+        dynamic_table::read_column(&table.columns[0], 1..3).unwrap(),
+        ["[] (0)", "[0.3, 0.4, 0.5] (3)"]
+    );
+    assert_eq!(
+        dynamic_table::read_column(&table.columns[1], 0..3).unwrap(),
+        [
+            "[[1] (1), [2, 3] (2)] (2)",
+            "[[4] (1)] (1)",
+            "[[5, 6] (2)] (1)"
+        ]
+    );
+    assert_eq!(
+        dynamic_table::read_values::<i64>(&table.columns[1], 1..3).unwrap(),
+        [vec![4], vec![5, 6]]
+    );
+}
+
+#[test]
+fn resolve_region_rows() {
+    let path = std::env::temp_dir().join("nwbview_dynamic_table_region.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let electrodes = file.create_group("electrodes").unwrap();
+        write(&electrodes, "id", &[10_i64, 11, 12]);
+        write(&electrodes, "location", &text(&["CA1", "CA3", "DG"]));
+        let units = file.create_group("units").unwrap();
+        units
+            .new_attr_builder()
+            .with_data(&text(&["electrodes"]))
+            .create("colnames")
+            .unwrap();
+        write(&units, "id", &[0_i64, 1]);
+        write(&units, "electrodes", &[2_i32, 0, 1]);
+        write(&units, "electrodes_index", &[2_u64, 3]);
+        let reference = file
+            .reference::<hdf5::ObjectReference1>("electrodes")
+            .unwrap();
+        units
+            .dataset("electrodes")
+            .unwrap()
+            .new_attr::<hdf5::ObjectReference1>()
+            .create("table")
+            .unwrap()
+            .write_scalar(&reference)
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let table = dynamic_table::DynamicTable::open(&file.group("units").unwrap()).unwrap();
+    let region = table.columns[0].region.as_ref().unwrap();
+    assert_eq!(region.table, "/electrodes");
+    assert_eq!(region.label(2), "12 (DG)");
+    assert_eq!(region.label(3), "3 (out of range)");
+    assert_eq!(
+        dynamic_table::read_values::<i64>(&table.columns[0], 0..2).unwrap(),
+        [vec![2, 0], vec![1]]
+    );
+    let electrodes = file.dataset("units/electrodes").unwrap();
+    assert!(dynamic_table::Region::open(&electrodes).is_some());
+    assert!(dynamic_table::Region::open(&file.dataset("units/id").unwrap()).is_none());
+}
+
+#[test]
+fn electrode_labels_from_region() {
+    let path = std::env::temp_dir().join("nwbview_electrode_labels.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let table = file.create_group("electrodes").unwrap();
+        table
+            .new_dataset_builder()
+            .with_data(&[10_i64, 11, 12])
+            .create("id")
+            .unwrap();
+        let locations: Vec<hdf5::types::VarLenUnicode> = ["CA1", "CA3", "DG"]
+            .iter()
+            .map(|x| x.parse().unwrap())
+            .collect();
+        table
+            .new_dataset_builder()
+            .with_data(&locations)
+            .create("location")
+            .unwrap();
+        let series = file.create_group("series").unwrap();
+        let region = series
+            .new_dataset_builder()
+            .with_data(&[2_i64, 0])
+            .create("electrodes")
+            .unwrap();
+        let reference = file
+            .reference::<hdf5::ObjectReference1>("electrodes")
+            .unwrap();
+        region
+            .new_attr::<hdf5::ObjectReference1>()
+            .create("table")
+            .unwrap()
+            .write_scalar(&reference)
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    assert_eq!(
+        dynamic_table::electrode_labels(&file.group("series").unwrap()),
+        Some(vec!["12 (DG)".to_string(), "10 (CA1)".to_string()])
+    );
+    assert_eq!(dynamic_table::electrode_labels(&file), None);
+}
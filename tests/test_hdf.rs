@@ -1,25 +1,41 @@
 #[cfg(test)]
+#[allow(dead_code)]
 #[path = "../src/hdf.rs"]
 mod hdf;
 
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
 #[test]
 fn read_valid_file() {
-    assert_eq!(
-        hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb").is_none(),
-        false
+    assert!(
+        hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb").is_some()
     );
 }
 
 #[test]
 fn read_invalid_file() {
-    assert_eq!(hdf::read_nwb_file("data/UNKNOWN.nwb").is_none(), true);
+    assert!(hdf::read_nwb_file("data/UNKNOWN.nwb").is_none());
 }
 
 #[test]
 fn get_subgroups_from_file() {
     let input_file =
         hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb");
-    let groups = input_file.unwrap().tree.groups;
+    let input_file = input_file.unwrap();
+    let groups = &input_file.tree.groups;
     let expected_groups: Vec<String> = vec![
         "/acquisition".to_string(),
         "/analysis".to_string(),
@@ -33,3 +49,192 @@ fn get_subgroups_from_file() {
         assert_eq!(group.0.handler.name(), *group.1);
     }
 }
+
+#[test]
+fn read_root_attributes() {
+    let input_file =
+        hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb");
+    let tree = input_file.unwrap().tree;
+    let neurodata_type = tree
+        .attributes
+        .iter()
+        .find(|x| x.name == "neurodata_type")
+        .unwrap();
+    assert_eq!(neurodata_type.value.to_string(), "NWBFile");
+}
+
+#[test]
+fn enum_member_names() {
+    use hdf5::types::{EnumMember, EnumType, IntSize};
+    let enum_type = EnumType {
+        size: IntSize::U1,
+        signed: true,
+        members: vec![
+            EnumMember {
+                name: "LEFT".to_string(),
+                value: 0xFF,
+            },
+            EnumMember {
+                name: "RIGHT".to_string(),
+                value: 1,
+            },
+        ],
+    };
+    assert_eq!(hdf::enum_member_name(&enum_type, -1), Some("LEFT"));
+    assert_eq!(hdf::enum_member_name(&enum_type, 1), Some("RIGHT"));
+    assert_eq!(hdf::enum_member_name(&enum_type, 2), None);
+    assert_eq!(hdf::enum_label(&enum_type, 1), "RIGHT (1)");
+}
+
+#[test]
+fn build_tree_with_links() {
+    let dir = std::env::temp_dir();
+    let path = dir.join("nwbview_links.h5");
+    let external_path = dir.join("nwbview_links_external.h5");
+    {
+        let external = hdf5::File::create(&external_path).unwrap();
+        external.create_group("remote").unwrap();
+        let file = hdf5::File::create(&path).unwrap();
+        let group = file.create_group("group").unwrap();
+        group.link_hard("/group", "cycle").unwrap();
+        group.link_soft("/group", "soft").unwrap();
+        group.link_soft("/missing", "dangling").unwrap();
+        file.link_external(external_path.to_str().unwrap(), "/remote", "external")
+            .unwrap();
+    }
+    let tree = hdf::read_nwb_file(path.to_str().unwrap()).unwrap().tree;
+    assert_eq!(tree.groups.len(), 1);
+    let group = &tree.groups[0];
+    assert!(group.groups.is_empty());
+    let link = |tree: &hdf::GroupTree, name: &str| {
+        let link = tree.links.iter().find(|x| x.name == name).unwrap();
+        (link.kind, link.target.clone())
+    };
+    let group_target = Some("/group".to_string());
+    assert_eq!(
+        link(group, "/group/cycle"),
+        (hdf5::LinkType::Hard, group_target.clone())
+    );
+    assert_eq!(
+        link(group, "/group/soft"),
+        (hdf5::LinkType::Soft, group_target)
+    );
+    assert_eq!(link(group, "/group/dangling"), (hdf5::LinkType::Soft, None));
+    assert_eq!(link(&tree, "/external"), (hdf5::LinkType::External, None));
+    let external = tree.links.iter().find(|x| x.name == "/external").unwrap();
+    assert_eq!(
+        hdf::external_target(&tree.handler, external),
+        Some((
+            "/remote".to_string(),
+            external_path.to_str().unwrap().to_string()
+        ))
+    );
+}
+
+#[test]
+fn read_neurodata_types() {
+    let input_file =
+        hdf::read_nwb_file("data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb").unwrap();
+    let neurodata_type = input_file.tree.neurodata_type.clone().unwrap();
+    assert_eq!(neurodata_type.name, "NWBFile");
+    assert_eq!(neurodata_type.to_string(), "core.NWBFile");
+    let spec = input_file.specification.get(&neurodata_type).unwrap();
+    assert!(!spec.doc.is_empty());
+}
+
+#[test]
+fn sample_times_from_rate() {
+    let path = std::env::temp_dir().join("nwbview_sample_times.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        file.create_group("empty").unwrap();
+        let starting_time = file.new_dataset::<f64>().create("starting_time").unwrap();
+        starting_time.write_scalar(&2.0).unwrap();
+        starting_time
+            .new_attr::<f64>()
+            .create("rate")
+            .unwrap()
+            .write_scalar(&4.0)
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    assert_eq!(hdf::sample_times(&file, 3), Some(vec![2.0, 2.25, 2.5]));
+    assert_eq!(hdf::sample_times(&file.group("empty").unwrap(), 3), None);
+}
+
+#[test]
+fn scaling_from_attributes() {
+    let path = std::env::temp_dir().join("nwbview_scaling.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let data = file
+            .new_dataset_builder()
+            .with_data(&[1_i16, 2, 3])
+            .create("data")
+            .unwrap();
+        for (name, value) in [("conversion", 0.5), ("offset", -1.0)] {
+            data.new_attr::<f64>()
+                .create(name)
+                .unwrap()
+                .write_scalar(&value)
+                .unwrap();
+        }
+        let unit: hdf5::types::VarLenUnicode = "volts".parse().unwrap();
+        data.new_attr::<hdf5::types::VarLenUnicode>()
+            .create("unit")
+            .unwrap()
+            .write_scalar(&unit)
+            .unwrap();
+        file.new_dataset_builder()
+            .with_data(&[1.0_f64])
+            .create("plain")
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let scaling = hdf::Scaling::read(&file.dataset("data").unwrap());
+    assert_eq!(scaling.unit.as_deref(), Some("volts"));
+    assert_eq!(scaling.apply(4.0), 1.0);
+    assert!(!scaling.is_identity());
+    assert!(hdf::Scaling::read(&file.dataset("plain").unwrap()).is_identity());
+}
+
+#[derive(hdf5::H5Type, Clone, Copy)]
+#[repr(i8)]
+enum Side {
+    Left = -1,
+    Right = 1,
+}
+
+#[test]
+fn enum_channels_by_column() {
+    let path = std::env::temp_dir().join("nwbview_enum_channels.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        file.new_dataset::<Side>()
+            .shape((3, 2))
+            .create("sides")
+            .unwrap()
+            .write_raw(&[
+                Side::Left,
+                Side::Right,
+                Side::Right,
+                Side::Right,
+                Side::Left,
+                Side::Left,
+            ])
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let channels = hdf::enum_channels(&file.dataset("sides").unwrap()).unwrap();
+    assert_eq!(channels, [[-1.0, 1.0, -1.0], [1.0, 1.0, -1.0]]);
+}
+
+#[test]
+fn read_file_with_progress() {
+    let path = "data/sub-anm266951_ses-20141201_behavior+icephys+ogen.nwb";
+    let progress = job::Progress::default();
+    assert!(hdf::read_nwb_file_with(path, &progress).is_some());
+    assert!(progress.done() > 0);
+    progress.cancel();
+    assert!(hdf::read_nwb_file_with(path, &progress).is_none());
+}
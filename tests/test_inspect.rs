@@ -0,0 +1,107 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/inspect.rs"]
+mod inspect;
+
+fn write_attribute(location: &hdf5::Location, name: &str, value: &str) {
+    let value: hdf5::types::VarLenUnicode = value.parse().unwrap();
+    location
+        .new_attr::<hdf5::types::VarLenUnicode>()
+        .create(name)
+        .unwrap()
+        .write_scalar(&value)
+        .unwrap();
+}
+
+/// The file is written once, the tests run in parallel
+fn inspect_test_file() -> &'static [inspect::Finding] {
+    static FINDINGS: std::sync::OnceLock<Vec<inspect::Finding>> = std::sync::OnceLock::new();
+    FINDINGS.get_or_init(write_inspect_test_file)
+}
+
+fn write_inspect_test_file() -> Vec<inspect::Finding> {
+    let path = std::env::temp_dir().join("nwbview_inspect.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        write_attribute(&file, "neurodata_type", "NWBFile");
+        let behavior = file.create_group("behavior").unwrap();
+        write_attribute(&behavior, "neurodata_type", "ProcessingModule");
+        let series = file.create_group("series").unwrap();
+        write_attribute(&series, "neurodata_type", "TimeSeries");
+        write_attribute(&series, "description", "no description");
+        let data = series
+            .new_dataset_builder()
+            .with_data(&[0.0_f64; 0])
+            .create("data")
+            .unwrap();
+        data.new_attr::<f64>()
+            .create("conversion")
+            .unwrap()
+            .write_scalar(&0.0)
+            .unwrap();
+        series
+            .new_dataset_builder()
+            .with_data(&[0.0_f64, 2.0, 1.0])
+            .create("timestamps")
+            .unwrap();
+        series
+            .new_dataset::<f64>()
+            .create("starting_time")
+            .unwrap()
+            .write_scalar(&0.0)
+            .unwrap();
+    }
+    let file_tree = hdf::read_nwb_file(path.to_str().unwrap()).unwrap();
+    inspect::inspect(&file_tree.tree, &inspect::default_checks())
+}
+
+#[test]
+fn report_best_practice_findings() {
+    let findings = inspect_test_file();
+    let checks: Vec<(inspect::Severity, &str, &str)> = findings
+        .iter()
+        .map(|x| (x.severity, x.check, x.path.as_str()))
+        .collect();
+    use inspect::Severity::{Critical, Warning};
+    assert_eq!(
+        checks,
+        [
+            (Critical, "timestamps_ascending", "/series/timestamps"),
+            (Warning, "session_metadata", "/"),
+            (Warning, "missing_description", "/behavior"),
+            (Warning, "missing_description", "/series"),
+            (Warning, "rate_with_timestamps", "/series"),
+            (Warning, "suspicious_conversion", "/series/data"),
+            (Warning, "empty_dataset", "/series/data"),
+        ]
+    );
+}
+
+#[test]
+fn findings_to_json() {
+    let findings = inspect_test_file();
+    let json = inspect::to_json("test.nwb", &findings[..1]);
+    assert_eq!(json[0]["file"], "test.nwb");
+    assert_eq!(json[0]["severity"], "critical");
+    assert_eq!(json[0]["path"], "/series/timestamps");
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+fn wait<T>(job: &job::Job<T>) -> job::Status<T> {
+    loop {
+        match job.poll() {
+            job::Status::Running => std::thread::sleep(std::time::Duration::from_millis(1)),
+            x => return x,
+        }
+    }
+}
+
+#[test]
+fn job_returns_its_result() {
+    let job = job::Job::spawn("sum", |progress| {
+        progress.set_total(10);
+        progress.set_done(10);
+        Ok((1..=10).sum::<i32>())
+    });
+    assert!(matches!(wait(&job), job::Status::Done(55)));
+
+    let job = job::Job::spawn("failure", |_| Err::<(), _>("broken".to_string()));
+    assert!(matches!(wait(&job), job::Status::Failed(x) if x == "broken"));
+}
+
+#[test]
+fn panicking_job_fails() {
+    let job = job::Job::spawn("panic", |_| -> Result<(), String> {
+        panic!("out of bounds")
+    });
+    assert!(matches!(wait(&job), job::Status::Failed(x) if x.contains("out of bounds")));
+}
+
+#[test]
+fn cancelled_job_has_no_result() {
+    let job = job::Job::spawn("endless", |progress| {
+        while !progress.is_cancelled() {
+            progress.advance(1);
+            std::thread::yield_now();
+        }
+        Ok(())
+    });
+    job.cancel();
+    assert!(matches!(wait(&job), job::Status::Cancelled));
+}
@@ -0,0 +1,150 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/lod.rs"]
+mod lod;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+fn indices(n: usize) -> Vec<f64> {
+    (0..n).map(|x| x as f64).collect()
+}
+
+#[test]
+fn envelope_keeps_short_peaks() {
+    let mut data = vec![0.0; 100_000];
+    data[54_321] = 5.0;
+    data[12_345] = -3.0;
+    let times = lod::Times::Samples(indices(data.len()));
+    let pyramid = lod::Pyramid::new(&data);
+    let points = pyramid.envelope(&times, 0..data.len(), 100).unwrap();
+    assert!(points.len() <= 2 * 100);
+    let max = points
+        .iter()
+        .map(|x| x[1])
+        .fold(f64::NEG_INFINITY, f64::max);
+    let min = points.iter().map(|x| x[1]).fold(f64::INFINITY, f64::min);
+    assert_eq!((min, max), (-3.0, 5.0));
+    assert_eq!(points.first().unwrap()[0], 0.0);
+    assert_eq!(points.last().unwrap()[0], 99_999.0);
+    assert_eq!(pyramid.extrema(), Some((-3.0, 5.0)));
+}
+
+#[test]
+fn envelope_of_few_samples_is_raw() {
+    let data = vec![1.0, 3.0, 2.0, 5.0, 4.0];
+    let x = indices(data.len());
+    let pyramid = lod::Pyramid::new(&data);
+    assert_eq!(
+        pyramid.envelope(&lod::Times::Samples(x.clone()), 1..4, 100),
+        None
+    );
+    assert_eq!(
+        lod::raw_envelope(&x[1..4], &data[1..4], 100),
+        vec![[1.0, 3.0], [2.0, 2.0], [3.0, 5.0]]
+    );
+}
+
+#[test]
+fn visible_range_is_widened() {
+    let x = vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5];
+    assert_eq!(lod::visible_range(&x, 0.7, 1.6), 1..5);
+    assert_eq!(lod::visible_range(&x, -5.0, 10.0), 0..6);
+    assert_eq!(lod::visible_range(&x, 10.0, 20.0), 5..6);
+    let rate = lod::Times::Rate {
+        start: 0.0,
+        rate: 2.0,
+    };
+    assert_eq!(rate.visible_range(0.7, 1.6, 6), 0..5);
+    assert_eq!(rate.visible_range(-5.0, 10.0, 6), 0..6);
+}
+
+#[test]
+fn overview_from_file() {
+    let path = std::env::temp_dir().join("nwbview_overview.h5");
+    let sidecar = std::env::temp_dir().join("nwbview_overview.lod");
+    let n_rows = 10_000;
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let values: Vec<f64> = (0..2 * n_rows).map(|x| (x % 2 * x) as f64).collect();
+        let data = file
+            .new_dataset::<f64>()
+            .shape((n_rows, 2))
+            .create("data")
+            .unwrap();
+        data.write_raw(&values).unwrap();
+        file.new_dataset_builder()
+            .with_data(&indices(n_rows))
+            .create("timestamps")
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let data = file.dataset("data").unwrap();
+    let timestamps = file.dataset("timestamps").unwrap();
+    let progress = job::Progress::default();
+    let block = lod::base_block(n_rows, 2);
+    let overview = lod::build_overview(&data, Some(&timestamps), block, &progress)
+        .unwrap()
+        .unwrap();
+    assert_eq!((progress.done(), progress.total()), (n_rows, n_rows));
+    assert_eq!(overview.channels[0].extrema(), Some((0.0, 0.0)));
+    assert_eq!(
+        overview.channels[1].extrema(),
+        Some((1.0, (2 * n_rows - 1) as f64))
+    );
+    let times = overview.times.unwrap();
+    assert_eq!(times.visible_range(100.0, 200.0, n_rows), 64..256);
+
+    lod::save_overview(&sidecar, 7, &overview.channels, &times).unwrap();
+    let loaded = lod::load_overview(&sidecar, 7, n_rows, 2).unwrap();
+    assert_eq!(loaded.channels, overview.channels);
+    assert_eq!(loaded.times, Some(times));
+    assert!(lod::load_overview(&sidecar, 8, n_rows, 2).is_none());
+}
+
+#[test]
+fn envelope_follows_falling_signals() {
+    let data: Vec<f64> = (0..1000).rev().map(|x| x as f64).collect();
+    let x = indices(data.len());
+    let points = lod::raw_envelope(&x, &data, 10);
+    assert_eq!(points[0], [0.0, 999.0]);
+    assert_eq!(points[1], [99.0, 900.0]);
+    assert!(points.windows(2).all(|x| x[1][1] <= x[0][1]));
+    let pyramid = lod::Pyramid::new(&data);
+    let points = pyramid
+        .envelope(&lod::Times::Samples(x), 0..data.len(), 10)
+        .unwrap();
+    assert!(points.windows(2).all(|x| x[1][1] <= x[0][1]));
+}
+
+#[test]
+fn empty_series_have_no_extrema() {
+    let pyramid = lod::Pyramid::new(&[]);
+    assert_eq!(pyramid.extrema(), None);
+    assert_eq!(
+        pyramid.envelope(&lod::Times::Samples(vec![]), 0..0, 100),
+        None
+    );
+    assert!(lod::raw_envelope(&[], &[], 100).is_empty());
+}
+
+#[test]
+fn finest_level_draws_one_bucket_per_block() {
+    let data: Vec<f64> = (0..64).map(|x| (x % 8) as f64).collect();
+    let times = lod::Times::Samples(indices(data.len()));
+    let pyramid = lod::Pyramid::new(&data);
+    assert_eq!(pyramid.envelope(&times, 8..32, 10), None);
+    assert_eq!(
+        pyramid.finest_envelope(&times, 8..32),
+        vec![
+            [8.0, 0.0],
+            [15.0, 7.0],
+            [16.0, 0.0],
+            [23.0, 7.0],
+            [24.0, 0.0],
+            [31.0, 7.0]
+        ]
+    );
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+const NAMESPACE: &str = r#"{"namespaces": [
+    {"name": "ndx-test", "version": "0.1.0", "schema": [{"namespace": "core"}, {"source": "ndx-test.extensions"}]},
+    {"name": "core", "version": "2.5.0", "schema": [{"source": "nwb.base"}]}
+]}"#;
+
+const CORE: &str = r#"{"groups": [
+    {"neurodata_type_def": "TimeSeries", "neurodata_type_inc": "NWBDataInterface",
+     "doc": "General purpose time series.",
+     "attributes": [{"name": "description", "dtype": "text", "required": false}],
+     "datasets": [
+        {"name": "data", "dims": ["num_times"], "shape": [[null], [null, null]]},
+        {"name": "timestamps", "dtype": "float64", "shape": [null], "quantity": "?"}
+     ]},
+    {"neurodata_type_def": "NWBDataInterface", "neurodata_type_inc": "NWBContainer",
+     "doc": "An abstract data type."}
+]}"#;
+
+const EXTENSION: &str = "
+groups:
+- neurodata_type_def: LickSeries
+  neurodata_type_inc: TimeSeries
+  doc: Licks of the animal.
+  datasets:
+  - name: data
+    dtype: uint8
+    shape:
+    - null
+";
+
+fn registry() -> spec::Registry {
+    let mut registry = spec::Registry::default();
+    registry.add_namespaces(&spec::parse_document(NAMESPACE).unwrap());
+    registry.add_document("core", &spec::parse_document(CORE).unwrap());
+    registry.add_document("ndx-test", &spec::parse_document(EXTENSION).unwrap());
+    registry
+}
+
+fn neurodata_type(namespace: &str, name: &str) -> hdf::NeurodataType {
+    hdf::NeurodataType {
+        name: name.to_string(),
+        namespace: Some(namespace.to_string()),
+    }
+}
+
+#[test]
+fn extension_types_inherit_from_core() {
+    let registry = registry();
+    let lick_series = neurodata_type("ndx-test", "LickSeries");
+    let ancestors: Vec<&str> = registry
+        .ancestors(&lick_series)
+        .iter()
+        .map(|x| x.name.as_str())
+        .collect();
+    assert_eq!(ancestors, ["LickSeries", "TimeSeries", "NWBDataInterface"]);
+    assert_eq!(
+        registry.find("ndx-test", "TimeSeries").unwrap().namespace,
+        "core"
+    );
+}
+
+#[test]
+fn inherited_spec_overrides_members() {
+    let registry = registry();
+    let spec = registry
+        .inherited_spec(&neurodata_type("ndx-test", "LickSeries"))
+        .unwrap();
+    assert_eq!(spec.doc, "Licks of the animal.");
+    assert_eq!(spec.attributes.len(), 1);
+    assert!(!spec.attributes[0].required);
+    let data = spec
+        .datasets
+        .iter()
+        .find(|x| x.name.as_deref() == Some("data"));
+    assert_eq!(data.unwrap().dtype.as_deref(), Some("uint8"));
+    assert_eq!(data.unwrap().shapes, vec![vec![None]]);
+    let timestamps = &spec.datasets[1];
+    assert_eq!(timestamps.quantity(), spec::Quantity::ZeroOrOne);
+}
+
+#[test]
+fn unknown_types_are_not_found() {
+    let registry = registry();
+    assert!(registry.get(&neurodata_type("core", "Unknown")).is_none());
+    assert!(
+        registry
+            .inherited_spec(&neurodata_type("core", "Unknown"))
+            .is_none()
+    );
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/dynamic_table.rs"]
+mod dynamic_table;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spikes.rs"]
+mod spikes;
+
+fn text(values: &[&str]) -> Vec<hdf5::types::VarLenUnicode> {
+    values.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+fn write<T: hdf5::H5Type>(group: &hdf5::Group, name: &str, values: &[T]) {
+    group
+        .new_dataset_builder()
+        .with_data(values)
+        .create(name)
+        .unwrap();
+}
+
+/// Three units with 2, 0 and 3 spikes, and waveforms left out of the metadata
+fn write_units(file: &hdf5::File) {
+    let units = file.create_group("units").unwrap();
+    units
+        .new_attr_builder()
+        .with_data(&text(&["spike_times", "quality", "waveforms"]))
+        .create("colnames")
+        .unwrap();
+    write(&units, "id", &[7_i64, 8, 9]);
+    write(&units, "spike_times", &[0.5_f64, 1.5, 0.25, 1.0, 2.0]);
+    write(&units, "spike_times_index", &[2_u64, 2, 5]);
+    write(&units, "quality", &text(&["good", "noise", "Good"]));
+    write(&units, "waveforms", &[1.0_f64, 2.0, 3.0]);
+    write(&units, "waveforms_index", &[1_u64, 2, 3]);
+}
+
+#[test]
+fn read_units() {
+    let path = std::env::temp_dir().join("nwbview_spikes.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        write_units(&file);
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let group = file.group("units").unwrap();
+    let units = spikes::Units::read(&group, &job::Progress::default()).unwrap();
+    assert_eq!(units.ids, [7, 8, 9]);
+    assert_eq!(
+        units.spike_times,
+        [vec![0.5, 1.5], vec![], vec![0.25, 1.0, 2.0]]
+    );
+    assert_eq!(units.columns, ["quality"]);
+    assert_eq!(units.filter(0, "GOOD"), [0, 2]);
+    assert_eq!(units.filter(0, ""), [0, 1, 2]);
+    assert_eq!(units.time_range(), Some((0.25, 2.0)));
+    assert!(units.describe(1).starts_with("unit 8\n"));
+    assert!(units.describe(1).ends_with("quality: noise"));
+}
+
+#[test]
+fn visible_spikes_are_thinned() {
+    let times: Vec<f64> = (0..100).map(|x| x as f64 / 10.0).collect();
+    assert_eq!(
+        spikes::visible_spikes(&times, 2.05, 2.45, 10),
+        [2.1, 2.2, 2.3, 2.4]
+    );
+    let thinned = spikes::visible_spikes(&times, 0.0, 10.0, 5);
+    assert_eq!(thinned, [0.0, 2.0, 4.0, 6.0, 8.0]);
+    assert!(spikes::visible_spikes(&times, 20.0, 30.0, 5).is_empty());
+}
+
+#[test]
+fn read_trials_of_intervals() {
+    let path = std::env::temp_dir().join("nwbview_trials.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let intervals = file.create_group("intervals").unwrap();
+        let trials = intervals.create_group("trials").unwrap();
+        trials
+            .new_attr_builder()
+            .with_data(&text(&["start_time", "cue_time", "side"]))
+            .create("colnames")
+            .unwrap();
+        write(&trials, "id", &[0_i64, 1]);
+        write(&trials, "start_time", &[0.0_f64, 2.0]);
+        write(&trials, "cue_time", &[0.5_f64, f64::NAN]);
+        write(&trials, "side", &text(&["left", "right"]));
+        intervals.create_group("notes").unwrap();
+        let epochs = file
+            .create_group("processing")
+            .unwrap()
+            .create_group("epochs")
+            .unwrap();
+        for table in [&trials, &epochs] {
+            table
+                .new_attr::<hdf5::types::VarLenUnicode>()
+                .create("neurodata_type")
+                .unwrap()
+                .write_scalar(
+                    &"TimeIntervals"
+                        .parse::<hdf5::types::VarLenUnicode>()
+                        .unwrap(),
+                )
+                .unwrap();
+        }
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let tree = hdf::build_tree(file.as_group().unwrap(), &job::Progress::default());
+    assert_eq!(
+        spikes::interval_tables(&tree, &spec::Registry::default()),
+        ["/intervals/trials", "/processing/epochs"]
+    );
+    let trials = spikes::Trials::read(&file.group("intervals/trials").unwrap()).unwrap();
+    let events: Vec<&str> = trials.events.iter().map(|x| x.0.as_str()).collect();
+    assert_eq!(events, ["start_time", "cue_time"]);
+    assert_eq!(trials.categories[0].0, "side");
+    assert_eq!(trials.categories[0].1, ["left", "right"]);
+}
+
+#[test]
+fn histogram_of_aligned_spikes() {
+    let times = [0.1, 0.6, 0.9, 2.2, 2.3, 5.0];
+    let aligned = spikes::aligned_spikes(&times, &[0.5, 2.0, f64::NAN], (-0.5, 0.5));
+    assert_eq!(aligned.len(), 3);
+    assert_eq!(aligned[1].len(), 2);
+    assert!(aligned[2].is_empty());
+    let expected = [-0.4, 0.1, 0.4];
+    assert!(
+        aligned[0]
+            .iter()
+            .zip(expected)
+            .all(|(x, y)| (x - y).abs() < 1e-9)
+    );
+
+    let rates = spikes::psth(&[&aligned[0], &aligned[1]], (-0.5, 0.5), 0.25);
+    // 1 spike in [-0.5, -0.25), 0 in [-0.25, 0), 2 in [0, 0.25), 2 in [0.25, 0.5), over 2 trials
+    let expected = [2.0, 0.0, 4.0, 4.0];
+    assert!(
+        rates
+            .iter()
+            .zip(expected)
+            .all(|(x, y)| (x - y).abs() < 1e-9)
+    );
+    assert_eq!(rates.len(), 4);
+
+    let rates = spikes::psth(&[&aligned[0]], (-1e6, 1e6), 0.001);
+    assert_eq!(rates.len(), spikes::MAX_BINS);
+}
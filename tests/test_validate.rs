@@ -0,0 +1,94 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/validate.rs"]
+mod validate;
+
+const CORE: &str = r#"{"groups": [
+    {"neurodata_type_def": "TimeSeries", "doc": "General purpose time series.",
+     "attributes": [{"name": "description", "dtype": "text"}],
+     "datasets": [
+        {"name": "data", "shape": [null]},
+        {"name": "timestamps", "dtype": "float64", "quantity": "?"}
+     ]}
+]}"#;
+
+fn write_attribute(location: &hdf5::Location, name: &str, value: &str) {
+    let value: hdf5::types::VarLenUnicode = value.parse().unwrap();
+    location
+        .new_attr::<hdf5::types::VarLenUnicode>()
+        .create(name)
+        .unwrap()
+        .write_scalar(&value)
+        .unwrap();
+}
+
+#[test]
+fn report_missing_and_mismatching_members() {
+    let path = std::env::temp_dir().join("nwbview_validate.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let series = file.create_group("series").unwrap();
+        write_attribute(&series, "neurodata_type", "TimeSeries");
+        write_attribute(&series, "namespace", "core");
+        series
+            .new_dataset_builder()
+            .with_data(&[1_i32, 2, 3])
+            .create("timestamps")
+            .unwrap();
+        let unknown = file.create_group("unknown").unwrap();
+        write_attribute(&unknown, "neurodata_type", "Unknown");
+    }
+    let file_tree = hdf::read_nwb_file(path.to_str().unwrap()).unwrap();
+    let mut registry = spec::Registry::default();
+    registry.add_document("core", &spec::parse_document(CORE).unwrap());
+
+    let violations = validate::validate(&file_tree.tree, &registry);
+    let messages: Vec<(&str, &str)> = violations
+        .iter()
+        .map(|x| (x.path.as_str(), x.message.as_str()))
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            ("/series", "The required attribute 'description' is missing"),
+            ("/series", "The required dataset 'data' is missing"),
+            (
+                "/series/timestamps",
+                "Expected the data type float64, found int32"
+            ),
+            (
+                "/unknown",
+                "The type Unknown is not defined by the cached specification"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn files_without_specification_are_reported() {
+    let path = std::env::temp_dir().join("nwbview_validate_empty.h5");
+    hdf5::File::create(&path).unwrap();
+    let file_tree = hdf::read_nwb_file(path.to_str().unwrap()).unwrap();
+    let violations = validate::validate(&file_tree.tree, &file_tree.specification);
+    assert_eq!(violations.len(), 1);
+}
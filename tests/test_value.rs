@@ -0,0 +1,161 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/hdf.rs"]
+mod hdf;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/job.rs"]
+mod job;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/spec.rs"]
+mod spec;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../src/value.rs"]
+mod value;
+
+use value::Value;
+
+#[derive(hdf5::H5Type, Clone, Copy)]
+#[repr(C)]
+struct Span {
+    start: i32,
+    count: u16,
+}
+
+#[derive(hdf5::H5Type, Clone)]
+#[repr(C)]
+struct Event {
+    time: f32,
+    span: Span,
+    target: hdf5::ObjectReference1,
+    label: hdf5::types::VarLenUnicode,
+    samples: [i8; 3],
+}
+
+#[test]
+fn decode_nested_compounds() {
+    let path = std::env::temp_dir().join("nwbview_value_compound.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        file.create_group("acquisition").unwrap();
+        let target = file
+            .reference::<hdf5::ObjectReference1>("acquisition")
+            .unwrap();
+        let event = |time: f32, start: i32, label: &str| Event {
+            time,
+            span: Span { start, count: 2 },
+            target,
+            label: label.parse().unwrap(),
+            samples: [-1, 0, 1],
+        };
+        file.new_dataset_builder()
+            .with_data(&[event(0.5, 10, "first"), event(1.5, -20, "second")])
+            .create("events")
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let events = file.dataset("events").unwrap();
+    let descriptor = events.dtype().unwrap().to_descriptor().unwrap();
+    let names: Vec<String> = value::fields(&descriptor)
+        .into_iter()
+        .map(|x| x.0)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "time",
+            "span.start",
+            "span.count",
+            "target",
+            "label",
+            "samples"
+        ]
+    );
+
+    let values = value::read(&events, 1..2).unwrap();
+    assert_eq!(values.len(), 1);
+    let leaves: Vec<String> = values[0].leaves().iter().map(|x| x.to_string()).collect();
+    assert_eq!(
+        leaves,
+        [
+            "1.5",
+            "-20",
+            "2",
+            "/acquisition",
+            "second",
+            "[-1, 0, 1] (3)"
+        ]
+    );
+    let Value::Compound(fields) = &values[0] else {
+        panic!("not a compound");
+    };
+    assert_eq!(
+        fields[3],
+        (
+            "target".to_string(),
+            Value::Reference("/acquisition".to_string())
+        )
+    );
+    assert_eq!(fields[1].1.to_string(), "{start: -20, count: 2}");
+}
+
+#[test]
+fn decode_scalars() {
+    let path = std::env::temp_dir().join("nwbview_value_scalar.h5");
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        file.new_dataset::<u8>()
+            .create("flag")
+            .unwrap()
+            .write_scalar(&7_u8)
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let flag = file.dataset("flag").unwrap();
+    assert_eq!(
+        value::read(&flag, hdf5::Selection::All).unwrap(),
+        [Value::Unsigned(7)]
+    );
+}
+
+#[test]
+fn decode_long_fixed_strings_and_arrays() {
+    let path = std::env::temp_dir().join("nwbview_value_fixed.h5");
+    let long = "x".repeat(10000);
+    {
+        let file = hdf5::File::create(&path).unwrap();
+        let text = hdf5::types::FixedAscii::<10000>::from_ascii(&long).unwrap();
+        file.new_dataset_builder()
+            .with_data(&[text])
+            .create("text")
+            .unwrap();
+        let row: [i32; 100] = std::array::from_fn(|i| i as i32);
+        file.new_dataset_builder()
+            .with_data(&[row, row])
+            .create("rows")
+            .unwrap();
+    }
+    let file = hdf5::File::open(&path).unwrap();
+    let text = value::read(&file.dataset("text").unwrap(), hdf5::Selection::All).unwrap();
+    assert_eq!(text, [Value::Text(long)]);
+    let rows = value::read(&file.dataset("rows").unwrap(), 1..2).unwrap();
+    let Value::Array(row) = &rows[0] else {
+        panic!("not an array");
+    };
+    assert_eq!(row.len(), 100);
+    assert_eq!(row[99], Value::Integer(99));
+}
+
+#[test]
+fn target_first_row_of_regions() {
+    let region = Value::Region("/data".to_string(), hdf5::Selection::from(2..5));
+    assert_eq!(region.target(), Some(("/data".to_string(), Some(2))));
+    let reference = Value::Reference("/acquisition".to_string());
+    assert_eq!(reference.target(), Some(("/acquisition".to_string(), None)));
+    assert_eq!(Value::Integer(1).target(), None);
+}